@@ -1,18 +1,38 @@
 use std::{
+    collections::{BTreeMap, HashMap},
     env,
     fs::{self, File},
     io::Write,
     path::{Path, PathBuf},
 };
 
+use serde::Deserialize;
+use symphonia::core::audio::{Channels, SampleBuffer};
 use symphonia::core::formats::FormatOptions;
 use symphonia::core::io::MediaSourceStream;
-use symphonia::core::meta::MetadataOptions;
+use symphonia::core::meta::{MetadataOptions, MetadataRevision, StandardTagKey};
 use symphonia::core::probe::Hint;
 use symphonia::default::get_probe;
 
+#[path = "build/audio_module.rs"]
+mod audio_module;
+#[path = "build/ids.rs"]
+mod ids;
+#[path = "build/loop_points.rs"]
+mod loop_points;
+#[path = "build/loudness.rs"]
+mod loudness;
+#[path = "build/naming.rs"]
+mod naming;
+
+use audio_module::AudioModule;
+use loop_points::LoopTags;
+use loudness::LoudnessMeter;
+
 const ASSET_PATH_VAR: &str = "BEVY_ASSET_PATH";
 const OUTPUT_FILE_NAME: &str = "audio_controller.rs";
+const TOML_CONFIG_NAME: &str = "audio.toml";
+const RON_CONFIG_NAME: &str = "audio.ron";
 
 fn main() {
     cargo_emit::rerun_if_env_changed!(ASSET_PATH_VAR);
@@ -21,6 +41,7 @@ fn main() {
 
     let mut marker_file = File::create(Path::new(&out_dir).join(OUTPUT_FILE_NAME)).unwrap();
     let mut files = Vec::new();
+    let mut config = AudioConfig::new();
 
     // Check if env variable is set for the assets folder
     if let Some(dir) = env::var(ASSET_PATH_VAR)
@@ -87,10 +108,11 @@ fn main() {
                     path = path.replace(std::path::MAIN_SEPARATOR, "/");
                 }
                 cargo_emit::rerun_if_changed!(full_path.to_string_lossy());
-                if let Some(duration) = get_audio_duration(&full_path) {
-                    files.push(AudioFile { path, duration });
+                if let Some(file) = get_audio_file(full_path, path) {
+                    files.push(file);
                 }
             });
+        config = read_config(&dir);
     } else if std::env::var("DOCS_RS").is_ok() {
         //         let out_dir = env::var_os("OUT_DIR").unwrap();
         //         let dest_path = Path::new(&out_dir).join("audio_lengths.rs");
@@ -110,6 +132,12 @@ fn main() {
         // panic!("No asset folder found");
     }
 
+    // `read_dir` order depends on the file system, sort so every build generates the same code
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    check_id_collisions(&files);
+    assign_identifiers(&mut files);
+    apply_config(&mut files, config);
+
     // Write the markers
     marker_file
         .write_all(
@@ -154,14 +182,14 @@ mod ac_traits {{
         fn insert_audio_track(&mut self, id: &AudioFiles) -> &mut EntityCommands<'a> {{
             match id {{
                 {}
-                AudioFiles::Unknown => self,
+                AudioFiles::Unknown | AudioFiles::Dynamic(_) => self,
             }}
         }}
 
         fn remove_audio_track(&mut self, id: &AudioFiles) -> &mut EntityCommands<'a> {{
             match id {{
                 {}
-                AudioFiles::Unknown => self,
+                AudioFiles::Unknown | AudioFiles::Dynamic(_) => self,
             }}
         }}
     }}
@@ -189,29 +217,73 @@ mod ac_traits {{
                     r#"
 pub mod audio_files {{
     #![allow(unused)]
+    #![allow(clippy::match_single_binding)]
 
-    use std::path::Path;
+    use std::{{fmt, path::Path, str::FromStr}};
 
     use bevy::{{core::Name, log::warn}};
+
+    use crate::dynamic::DynamicAudio;
     #[cfg(feature = "inspect")]
     use bevy::{{ecs::reflect::ReflectComponent, reflect::Reflect}};
     
-    /// Contains the path and duration of the audio file
+    /// Contains the path, duration, technical properties, and tags of the audio file
+    ///
+    /// Everything here is read from the file at build time, so it can be used without touching the file system
     #[derive(Debug, Default)]
     #[cfg_attr(feature = "inspect", derive(Reflect))]
     pub struct AudioFile {{
         pub path: &'static str,
+        /// Duration in seconds
         pub duration: f32,
+        /// Samples per second, in Hz
+        pub sample_rate: u32,
+        /// Number of audio channels, e.g. 2 for stereo
+        pub channels: u16,
+        /// Bits per sample, if the codec reports one
+        pub bit_depth: Option<u32>,
+        /// Short name of the codec, e.g. "vorbis"
+        pub codec: &'static str,
+        /// Size of the file in bytes
+        pub file_size: u64,
+        pub title: Option<&'static str>,
+        pub artist: Option<&'static str>,
+        pub album: Option<&'static str>,
+        /// Multiple comment tags are joined with a newline
+        pub comments: Option<&'static str>,
+        /// Integrated loudness in LUFS (ITU-R BS.1770), `None` if the file is silent
+        pub loudness: Option<f32>,
+        /// True peak in dBTP, `None` if the file is silent
+        pub true_peak: Option<f32>,
+        /// Loop region read from `LOOPSTART`/`LOOPLENGTH`/`LOOPEND` tags or a WAV `smpl` chunk
+        pub loop_points: Option<LoopPoints>,
+        /// Tags from the `audio.toml` or `audio.ron` file in your assets folder
+        #[cfg_attr(feature = "inspect", reflect(ignore))]
+        pub tags: &'static [&'static str],
+    }}
+
+    /// A loop region of an audio file, measured in sample frames
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "inspect", derive(Reflect))]
+    pub struct LoopPoints {{
+        /// First frame of the loop
+        pub start: u64,
+        /// Frame after the last frame of the loop
+        pub end: u64,
     }}
 
     /// This is your "library" of audio files. It is a convenient way to safely spawn audio files in your game without having to rely on "magic strings".
     /// 
-    /// If you need to insert strings dynamically from a file or a network request, you can use the `From<&str>` implementation to convert it to an `AudioFiles` enum.
+    /// If you need to insert strings dynamically from a file or a network request, you can use the `FromStr` or `TryFrom<&str>` implementations to convert it to an `AudioFiles` enum.
+    ///
+    /// With the `serde` feature enabled, it is serialized as its asset path so save files and network messages do not depend on the enum layout.
     #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
     #[cfg_attr(feature = "inspect", derive(Reflect))]
     pub enum AudioFiles {{
         #[default]
         Unknown,
+        /// A file registered at runtime through the [DynamicAudioRegistry](crate::dynamic::DynamicAudioRegistry)
+        Dynamic(DynamicAudio),
         {}
     }}
 
@@ -219,37 +291,63 @@ pub mod audio_files {{
         {}
     ];
 
-    impl ToString for AudioFiles {{
-        fn to_string(&self) -> String {{
-            match self {{
+    /// Returned when a string does not match the path of any audio file in your assets folder
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct UnknownAudioFileError(pub String);
+
+    impl fmt::Display for UnknownAudioFileError {{
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {{
+            write!(f, "Unknown audio file '{{}}'", self.0)
+        }}
+    }}
+
+    impl std::error::Error for UnknownAudioFileError {{}}
+
+    /// Displays the asset path of the file, or the id of a [DynamicAudio], which is also what [FromStr] parses
+    impl fmt::Display for AudioFiles {{
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {{
+            f.write_str(match self {{
                 {}
                 Self::Unknown => "Unknown",
-            }}
-            .to_string()
+                Self::Dynamic(dynamic) => return dynamic.fmt(f),
+            }})
         }}
     }}
 
-    impl From<&str> for AudioFiles {{
-        fn from(file_name: &str) -> Self {{
+    impl FromStr for AudioFiles {{
+        type Err = UnknownAudioFileError;
+
+        fn from_str(file_name: &str) -> Result<Self, Self::Err> {{
             match file_name.replace('\\', "/").as_str() {{
                 {}
-                unknown => {{
-                    warn!("Unknown audio file '{{}}' requested", unknown);
-                    AudioFiles::Unknown
-                }}
+                "Unknown" => Ok(AudioFiles::Unknown),
+                dynamic if dynamic.starts_with("dynamic:") => dynamic.parse().map(Self::Dynamic),
+                unknown => Err(UnknownAudioFileError(unknown.to_string())),
             }}
         }}
     }}
 
-    impl From<String> for AudioFiles {{
-        fn from(file_name: String) -> Self {{
-            Self::from(&file_name)
+    impl TryFrom<&str> for AudioFiles {{
+        type Error = UnknownAudioFileError;
+
+        fn try_from(file_name: &str) -> Result<Self, Self::Error> {{
+            file_name.parse()
+        }}
+    }}
+
+    impl TryFrom<String> for AudioFiles {{
+        type Error = UnknownAudioFileError;
+
+        fn try_from(file_name: String) -> Result<Self, Self::Error> {{
+            file_name.parse()
         }}
     }}
 
-    impl From<&String> for AudioFiles {{
-        fn from(file_name: &String) -> Self {{
-            Self::from(file_name.as_str())
+    impl TryFrom<&String> for AudioFiles {{
+        type Error = UnknownAudioFileError;
+
+        fn try_from(file_name: &String) -> Result<Self, Self::Error> {{
+            file_name.parse()
         }}
     }}
 
@@ -265,9 +363,27 @@ pub mod audio_files {{
         }}
     }}
 
-    impl From<&Name> for AudioFiles {{
-        fn from(name: &Name) -> Self {{
-            Self::from(&name.to_string())
+    impl TryFrom<&Name> for AudioFiles {{
+        type Error = UnknownAudioFileError;
+
+        fn try_from(name: &Name) -> Result<Self, Self::Error> {{
+            name.as_str().parse()
+        }}
+    }}
+
+    /// Serializes as the asset path of the file
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for AudioFiles {{
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{
+            serializer.collect_str(self)
+        }}
+    }}
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for AudioFiles {{
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{
+            let path = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+            path.parse().map_err(serde::de::Error::custom)
         }}
     }}
 
@@ -281,6 +397,10 @@ pub mod audio_files {{
                     warn!("Unknown audio file requested");
                     AudioFile::default()
                 }}
+                Self::Dynamic(dynamic) => {{
+                    warn!("{{}} is only known to the DynamicAudioRegistry", dynamic);
+                    AudioFile::default()
+                }}
             }}
         }}
 
@@ -291,6 +411,10 @@ pub mod audio_files {{
                     warn!("Unknown audio duration requested");
                     0.0
                 }}
+                Self::Dynamic(dynamic) => {{
+                    warn!("{{}} is only known to the DynamicAudioRegistry", dynamic);
+                    0.0
+                }}
             }}
         }}
 
@@ -301,6 +425,33 @@ pub mod audio_files {{
                     warn!("Unknown audio file name requested");
                     ""
                 }}
+                Self::Dynamic(dynamic) => {{
+                    warn!("{{}} is only known to the DynamicAudioRegistry", dynamic);
+                    ""
+                }}
+            }}
+        }}
+
+        /// A stable id for the file that does not change between builds or machines,
+        /// use it to reference sounds in replays, netcode, or compact save formats
+        ///
+        /// It is the 64-bit FNV-1a hash of the asset path with forward slashes, `Unknown` is always 0
+        pub const fn id(&self) -> u64 {{
+            match self {{
+        {}
+                Self::Unknown => 0,
+                Self::Dynamic(dynamic) => dynamic.0,
+            }}
+        }}
+
+        /// Returns the file with the given [AudioFiles::id], if it exists in this build
+        ///
+        /// Files registered at runtime are looked up with [DynamicAudioRegistry::from_id](crate::dynamic::DynamicAudioRegistry::from_id) instead
+        pub const fn from_id(id: u64) -> Option<Self> {{
+            match id {{
+        {}
+                0 => Some(Self::Unknown),
+                _ => None,
             }}
         }}
     }}
@@ -352,11 +503,57 @@ pub mod audio_files {{
                         .map(|f| f.get_path())
                         .collect::<Vec<_>>()
                         .join("\n        "),
+                    files
+                        .iter()
+                        .map(|f| f.get_id())
+                        .collect::<Vec<_>>()
+                        .join("\n        "),
+                    files
+                        .iter()
+                        .map(|f| f.get_from_id())
+                        .collect::<Vec<_>>()
+                        .join("\n        "),
                 )
                 .as_ref(),
             )
             .unwrap();
 
+    // Write the defaults from the config file
+    marker_file
+        .write_all(
+            format!(
+                r#"
+mod audio_config {{
+    #![allow(unused)]
+
+    use crate::{{audio_files::AudioFiles, delay_mode::DelayMode}};
+
+    /// The defaults of a file from the `audio.toml` or `audio.ron` file in your assets folder
+    pub(crate) struct TrackConfig {{
+        pub(crate) id: AudioFiles,
+        /// The channel type name, `None` for the global channel
+        pub(crate) channel: Option<&'static str>,
+        pub(crate) volume: Option<f32>,
+        pub(crate) speed: Option<f32>,
+        pub(crate) delay_mode: Option<DelayMode>,
+        pub(crate) looping: Option<bool>,
+    }}
+
+    pub(crate) const TRACK_CONFIGS: &[TrackConfig] = &[
+{}
+    ];
+}}
+"#,
+                files
+                    .iter()
+                    .filter_map(|f| f.track_config())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )
+            .as_ref(),
+        )
+        .unwrap();
+
     marker_file
         .write_all(
             format!(
@@ -374,13 +571,7 @@ mod ac_assets {{
 
     use super::audio_files::AudioFiles;
 
-    pub(super) fn load_assets(
-        asset_server: Res<AssetServer>,
-        mut internal_loader: ResMut<ACAssetLoader>,
-    ) {{
-{}
-    }}
-
+    /// Holds a handle for every file that has been requested, `None` if the file is not loaded
     #[derive(Default, Resource)]
     #[cfg_attr(feature = "inspect", derive(Reflect))]
     #[cfg_attr(feature = "inspect", reflect(Resource))]
@@ -392,7 +583,14 @@ mod ac_assets {{
         pub(super) fn get(&self, id: &AudioFiles) -> Option<Handle<AudioSource>> {{
             match id {{
 {}
-                AudioFiles::Unknown => None,
+                AudioFiles::Unknown | AudioFiles::Dynamic(_) => None,
+            }}
+        }}
+
+        pub(super) fn get_slot(&mut self, id: &AudioFiles) -> Option<&mut Option<Handle<AudioSource>>> {{
+            match id {{
+{}
+                AudioFiles::Unknown | AudioFiles::Dynamic(_) => None,
             }}
         }}
     }}
@@ -400,31 +598,116 @@ mod ac_assets {{
 "#,
                 files
                     .iter()
-                    .map(|f| f.asset_loader())
+                    .map(|f| f.asset_field())
                     .collect::<Vec<_>>()
                     .join("\n"),
                 files
                     .iter()
-                    .map(|f| f.asset_field())
+                    .map(|f| f.asset_getter())
                     .collect::<Vec<_>>()
                     .join("\n"),
                 files
                     .iter()
-                    .map(|f| f.asset_getter())
+                    .map(|f| f.asset_slot())
                     .collect::<Vec<_>>()
                     .join("\n")
             )
             .as_ref(),
         )
         .unwrap();
+
+    // Write the nested modules that mirror the asset folder
+    let mut root = AudioModule::default();
+    for file in files.iter() {
+        for (folder, module) in root.insert(&file.path, &file.pascal_case()) {
+            cargo_emit::warning!(
+                "The {} folder has the same module name as another folder, it has been renamed to {}",
+                folder,
+                module
+            );
+        }
+    }
+    marker_file
+        .write_all(
+            format!(
+                r#"
+/// Mirrors the folder structure of your assets folder, e.g. `audio::sfx::weapons::RIFLE_SHOT_01`
+///
+/// Every constant maps to the matching [audio_files::AudioFiles] variant, and every folder lists its files
+#[allow(unused)]
+pub mod audio {{
+{}}}
+"#,
+                root.render(1)
+            )
+            .as_ref(),
+        )
+        .unwrap();
 }
 
+#[derive(Default)]
 struct AudioFile {
     path: String,
     duration: f32,
+    sample_rate: u32,
+    channels: u16,
+    bit_depth: Option<u32>,
+    codec: String,
+    file_size: u64,
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    comments: Option<String>,
+    loudness: Option<f32>,
+    true_peak: Option<f32>,
+    loop_tags: LoopTags,
+    loop_points: Option<(u64, u64)>,
+    /// Defaults from the config file, assigned by [apply_config]
+    config: FileConfig,
+    /// Enum variant name, assigned by [assign_identifiers]
+    variant: String,
+    /// Field name, the const name is the uppercase version, assigned by [assign_identifiers]
+    field: String,
 }
 
 impl AudioFile {
+    fn read_tags(&mut self, revision: &MetadataRevision) {
+        let mut comments = Vec::new();
+        for tag in revision.tags() {
+            if self.loop_tags.read_tag(&tag.key, &tag.value.to_string()) {
+                continue;
+            }
+            let field = match tag.std_key {
+                Some(StandardTagKey::TrackTitle) => &mut self.title,
+                Some(StandardTagKey::Artist) => &mut self.artist,
+                Some(StandardTagKey::Album) => &mut self.album,
+                Some(StandardTagKey::Comment) => {
+                    comments.push(tag.value.to_string());
+                    continue;
+                }
+                _ => continue,
+            };
+            if field.is_none() {
+                *field = Some(tag.value.to_string());
+            }
+        }
+        if self.comments.is_none() && !comments.is_empty() {
+            self.comments = Some(comments.join("\n"));
+        }
+    }
+
+    fn resolve_loop_points(&self, total_frames: u64) -> Option<(u64, u64)> {
+        let loop_points = self.loop_tags.resolve(total_frames);
+        if let (Some(start), None) = (self.loop_tags.start(), loop_points) {
+            cargo_emit::warning!(
+                "Ignoring invalid loop points for {}: start {} is not before the end",
+                self.path,
+                start
+            );
+        }
+        loop_points
+    }
+
     fn build_iterable(&self) -> String {
         format!("AudioFiles::{},", self.pascal_case())
     }
@@ -432,7 +715,7 @@ impl AudioFile {
     fn get_enum_match(&self) -> String {
         let struct_name = self.pascal_case();
         format!(
-            "path if Path::new(path) == Path::new(Self::{}.path) => AudioFiles::{},",
+            "path if Path::new(path) == Path::new(Self::{}.path) => Ok(AudioFiles::{}),",
             self.snake_case().to_uppercase(),
             struct_name
         )
@@ -449,7 +732,7 @@ impl AudioFile {
     fn insert_audio_track_impl(&self) -> String {
         let struct_name = self.pascal_case();
         format!(
-            r#"AudioFiles::{} => self.insert({}::default()),"#,
+            r#"AudioFiles::{} => self.insert({}),"#,
             struct_name, struct_name
         )
     }
@@ -468,20 +751,23 @@ impl AudioFile {
 
     fn asset_field(&self) -> String {
         let field_name = self.snake_case();
-        format!(r#"        pub(super) {}: Handle<AudioSource>,"#, field_name)
+        format!(
+            r#"        pub(super) {}: Option<Handle<AudioSource>>,"#,
+            field_name
+        )
     }
 
-    fn asset_loader(&self) -> String {
+    fn asset_getter(&self) -> String {
         format!(
-            r#"        internal_loader.{} = asset_server.load(AudioFiles::{}.path());"#,
-            self.snake_case(),
-            self.pascal_case()
+            r#"                AudioFiles::{} => self.{}.clone(),"#,
+            self.pascal_case(),
+            self.snake_case()
         )
     }
 
-    fn asset_getter(&self) -> String {
+    fn asset_slot(&self) -> String {
         format!(
-            r#"                AudioFiles::{} => Some(self.{}.clone()),"#,
+            r#"                AudioFiles::{} => Some(&mut self.{}),"#,
             self.pascal_case(),
             self.snake_case()
         )
@@ -511,18 +797,87 @@ impl AudioFile {
         )
     }
 
+    fn get_id(&self) -> String {
+        format!("        Self::{} => {},", self.pascal_case(), self.id())
+    }
+
+    fn get_from_id(&self) -> String {
+        format!(
+            "        {} => Some(Self::{}),",
+            self.id(),
+            self.pascal_case()
+        )
+    }
+
+    fn id(&self) -> u64 {
+        ids::file_id(&self.path)
+    }
+
     fn audio_file_struct(&self) -> String {
         format!(
             "const {}: AudioFile = AudioFile {{
             path: {:?},
-            duration: {},
+            duration: {:?},
+            sample_rate: {},
+            channels: {},
+            bit_depth: {:?},
+            codec: {:?},
+            file_size: {},
+            title: {:?},
+            artist: {:?},
+            album: {:?},
+            comments: {:?},
+            loudness: {:?},
+            true_peak: {:?},
+            loop_points: {},
+            tags: &{:?},
         }};",
             self.snake_case().to_uppercase(),
             self.path,
-            self.duration
+            self.duration,
+            self.sample_rate,
+            self.channels,
+            self.bit_depth,
+            self.codec,
+            self.file_size,
+            self.title,
+            self.artist,
+            self.album,
+            self.comments,
+            self.loudness,
+            self.true_peak,
+            self.loop_points
+                .map_or("None".to_string(), |(start, end)| format!(
+                    "Some(LoopPoints {{ start: {}, end: {} }})",
+                    start, end
+                )),
+            self.config.tags,
         )
     }
 
+    fn track_config(&self) -> Option<String> {
+        let config = &self.config;
+        if config.channel.is_none()
+            && config.volume.is_none()
+            && config.speed.is_none()
+            && config.delay_mode.is_none()
+            && config.looping.is_none()
+        {
+            return None;
+        }
+        Some(format!(
+            "        TrackConfig {{ id: AudioFiles::{}, channel: {:?}, volume: {:?}, speed: {:?}, delay_mode: {}, looping: {:?} }},",
+            self.pascal_case(),
+            config.channel,
+            config.volume,
+            config.speed,
+            config
+                .delay_mode
+                .map_or("None".to_string(), |mode| format!("Some({})", mode.to_code())),
+            config.looping,
+        ))
+    }
+
     fn get_marker_struct(&self) -> String {
         let struct_name = self.pascal_case();
         format!(
@@ -541,40 +896,159 @@ impl AudioFile {
     }
 
     fn pascal_case(&self) -> String {
-        let mut parts: Vec<String> = self
-            .path
-            .split(|c: char| {
-                c.is_whitespace() || "-_.".contains(c) || c == std::path::MAIN_SEPARATOR
-            })
-            .filter(|s| !s.is_empty())
-            .map(|s| {
-                let mut chars = s.chars();
-                match chars.next() {
-                    Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
-                    None => String::new(),
-                }
-            })
-            .collect();
+        self.variant.clone()
+    }
 
-        if let Some(extension) = parts.last_mut() {
-            *extension = extension.to_uppercase();
+    fn snake_case(&self) -> String {
+        self.field.clone()
+    }
+}
+
+/// Per-file defaults keyed by asset path, read from `audio.toml` or `audio.ron` in the assets folder
+type AudioConfig = BTreeMap<String, FileConfig>;
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    channel: Option<String>,
+    volume: Option<f32>,
+    speed: Option<f32>,
+    delay_mode: Option<DelayModeConfig>,
+    #[serde(rename = "loop")]
+    looping: Option<bool>,
+    tags: Vec<String>,
+}
+
+/// Mirrors `DelayMode`, the build script can not depend on the crate itself
+#[derive(Deserialize, Clone, Copy)]
+enum DelayModeConfig {
+    Wait,
+    Immediate,
+    Percent(u16),
+    Milliseconds(i16),
+}
+
+impl DelayModeConfig {
+    fn to_code(self) -> String {
+        match self {
+            DelayModeConfig::Wait => "DelayMode::Wait".to_string(),
+            DelayModeConfig::Immediate => "DelayMode::Immediate".to_string(),
+            DelayModeConfig::Percent(percent) => format!("DelayMode::Percent({})", percent),
+            DelayModeConfig::Milliseconds(ms) => format!("DelayMode::Milliseconds({})", ms),
         }
+    }
+}
 
-        parts.concat()
+fn read_config(dir: &Path) -> AudioConfig {
+    let toml_path = dir.join(TOML_CONFIG_NAME);
+    let ron_path = dir.join(RON_CONFIG_NAME);
+    let config: AudioConfig = match (toml_path.exists(), ron_path.exists()) {
+        (true, true) => panic!(
+            "Found both {} and {} in your assets folder, please only use one of them",
+            TOML_CONFIG_NAME, RON_CONFIG_NAME
+        ),
+        (true, false) => {
+            cargo_emit::rerun_if_changed!(toml_path.to_string_lossy());
+            let contents = fs::read_to_string(&toml_path).unwrap();
+            toml::from_str(&contents)
+                .unwrap_or_else(|error| panic!("Invalid {}: {}", TOML_CONFIG_NAME, error))
+        }
+        (false, true) => {
+            cargo_emit::rerun_if_changed!(ron_path.to_string_lossy());
+            let contents = fs::read_to_string(&ron_path).unwrap();
+            ron::Options::default()
+                .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+                .from_str(&contents)
+                .unwrap_or_else(|error| panic!("Invalid {}: {}", RON_CONFIG_NAME, error))
+        }
+        (false, false) => AudioConfig::new(),
+    };
+    for path in config.keys() {
+        if !dir.join(path).is_file() {
+            panic!(
+                "The audio config has settings for {}, which does not exist in your assets folder",
+                path
+            );
+        }
     }
+    config
+}
 
-    fn snake_case(&self) -> String {
-        let mut snake_case = String::new();
-        let name = self.path.replace(&['/', '\\', '.', '-'][..], "_");
-        let mut prev_char = '\0';
-        for (i, ch) in name.chars().enumerate() {
-            if ch.is_uppercase() && i > 0 && prev_char != '_' {
-                snake_case.push('_');
+/// Checks the config values and attaches each entry to its file
+///
+/// Entries for files in a format that is not enabled in this build are skipped
+fn apply_config(files: &mut [AudioFile], config: AudioConfig) {
+    for (path, file_config) in config {
+        let Some(file) = files
+            .iter_mut()
+            .find(|file| file.path.replace('\\', "/") == path.replace('\\', "/"))
+        else {
+            continue;
+        };
+        if let Some(volume) = file_config.volume {
+            if !volume.is_finite() || volume < 0.0 {
+                panic!("The audio config volume for {} must not be negative", path);
             }
-            snake_case.push(ch.to_ascii_lowercase());
-            prev_char = ch;
         }
-        snake_case
+        if let Some(speed) = file_config.speed {
+            if !speed.is_finite() || speed <= 0.0 {
+                panic!("The audio config speed for {} must be positive", path);
+            }
+        }
+        file.config = file_config;
+    }
+}
+
+/// Ids are used as match arms, where a duplicate would only be an unreachable pattern warning and
+/// [AudioFiles::from_id] would silently return the first file, so uniqueness is enforced here
+fn check_id_collisions(files: &[AudioFile]) {
+    let mut ids = HashMap::<u64, &str>::new();
+    for file in files {
+        let id = file.id();
+        if id == 0 {
+            panic!(
+                "{} hashes to 0, which is reserved for AudioFiles::Unknown, please rename it",
+                file.path
+            );
+        }
+        if let Some(other) = ids.insert(id, &file.path) {
+            panic!(
+                "{} and {} have the same id ({}), please rename one of them",
+                other, file.path, id
+            );
+        }
+    }
+}
+
+/// Assigns the identifiers picked by [naming::assign_identifiers] and warns about renamed files
+fn assign_identifiers(files: &mut [AudioFile]) {
+    let paths: Vec<String> = files.iter().map(|file| file.path.clone()).collect();
+    let identifiers = naming::assign_identifiers(&paths);
+    for (file, (variant, field)) in files.iter_mut().zip(identifiers.names) {
+        file.variant = variant;
+        file.field = field;
+    }
+
+    for index in identifiers.sanitized {
+        cargo_emit::warning!(
+            "{} is not a valid identifier, using {} and {} instead",
+            files[index].path,
+            files[index].variant,
+            files[index].field
+        );
+    }
+    for group in identifiers.collisions {
+        cargo_emit::warning!(
+            "Audio files map to the same identifier, they have been renamed: {}",
+            group
+                .iter()
+                .map(|index| format!(
+                    "{} -> AudioFiles::{}",
+                    files[*index].path, files[*index].variant
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
     }
 }
 
@@ -610,16 +1084,17 @@ fn is_supported_audio_file(path: &Path) -> bool {
     if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
         formats.contains(&ext)
     } else {
-        return false;
+        false
     }
 }
 
-fn get_audio_duration(path: &Path) -> Option<f32> {
-    let file = std::fs::File::open(path).ok()?;
+fn get_audio_file(full_path: &Path, path: String) -> Option<AudioFile> {
+    let file = std::fs::File::open(full_path).ok()?;
+    let file_size = file.metadata().map_or(0, |meta| meta.len());
     let mss = MediaSourceStream::new(Box::new(file), Default::default());
     let hint = Hint::new();
 
-    let probed = get_probe()
+    let mut probed = get_probe()
         .format(
             &hint,
             mss,
@@ -629,22 +1104,86 @@ fn get_audio_duration(path: &Path) -> Option<f32> {
         .ok()?;
     let mut format = probed.format;
 
+    let mut audio_file = AudioFile {
+        path,
+        file_size,
+        ..Default::default()
+    };
+
+    // Tags from the container take priority over ones found while probing (e.g. ID3v2)
+    if let Some(revision) = format.metadata().current() {
+        audio_file.read_tags(revision);
+    }
+    if let Some(revision) = probed
+        .metadata
+        .get()
+        .as_mut()
+        .and_then(|metadata| metadata.skip_to_latest())
+    {
+        audio_file.read_tags(revision);
+    }
+
     if let Some(track) = format.default_track() {
         let codec_params = &track.codec_params;
-        let sample_rate = codec_params.sample_rate? as f32;
+        let sample_rate = codec_params.sample_rate?;
+
+        audio_file.sample_rate = sample_rate;
+        audio_file.channels = codec_params
+            .channels
+            .map_or(0, |channels| channels.count() as u16);
+        audio_file.bit_depth = codec_params.bits_per_sample;
+
+        let codecs = symphonia::default::get_codecs();
+        audio_file.codec = codecs
+            .get_codec(codec_params.codec)
+            .map_or("unknown", |descriptor| descriptor.short_name)
+            .to_string();
 
-        let mut decoder = symphonia::default::get_codecs()
-            .make(&codec_params, &Default::default())
-            .ok()?;
+        let mut decoder = codecs.make(codec_params, &Default::default()).ok()?;
         let mut total_frames = 0;
+        let mut meter: Option<LoudnessMeter> = None;
+        let mut samples: Option<SampleBuffer<f32>> = None;
 
         while let Ok(packet) = format.next_packet() {
             if let Ok(decoded) = decoder.decode(&packet) {
                 total_frames += decoded.frames();
+
+                let spec = *decoded.spec();
+                let buffer = samples
+                    .get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, spec));
+                if buffer.capacity() < decoded.capacity() * spec.channels.count() {
+                    *buffer = SampleBuffer::new(decoded.capacity() as u64, spec);
+                }
+                buffer.copy_interleaved_ref(decoded);
+                meter
+                    .get_or_insert_with(|| {
+                        let lfe: Vec<bool> = spec
+                            .channels
+                            .iter()
+                            .map(|channel| channel.intersects(Channels::LFE1 | Channels::LFE2))
+                            .collect();
+                        LoudnessMeter::new(sample_rate, &lfe)
+                    })
+                    .process(buffer.samples());
             }
         }
 
-        Some(total_frames as f32 / sample_rate)
+        audio_file.duration = total_frames as f32 / sample_rate as f32;
+        if audio_file.loop_tags.start().is_none()
+            && full_path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+        {
+            if let Ok(bytes) = fs::read(full_path) {
+                audio_file.loop_tags.read_wav(&bytes);
+            }
+        }
+        audio_file.loop_points = audio_file.resolve_loop_points(total_frames as u64);
+        if let Some(meter) = meter {
+            audio_file.loudness = meter.integrated_loudness();
+            audio_file.true_peak = meter.true_peak();
+        }
+        Some(audio_file)
     } else {
         None
     }
@@ -0,0 +1,219 @@
+use std::{collections::BTreeMap, path::Path};
+
+use crate::naming::{to_module_name, to_snake_case};
+
+/// A folder in the generated `audio` module tree
+#[derive(Default)]
+pub struct AudioModule {
+    /// Constant name and enum variant of the files directly in this folder
+    files: BTreeMap<String, String>,
+    /// Module name of every subfolder, folders whose names sanitize the same get a numeric suffix
+    folders: BTreeMap<String, String>,
+    children: BTreeMap<String, AudioModule>,
+}
+
+impl AudioModule {
+    /// Adds the constant of a file, returns the folders that had to be renamed as `(folder path, module name)`
+    pub fn insert(&mut self, path: &str, variant: &str) -> Vec<(String, String)> {
+        let mut parts: Vec<&str> = path
+            .split(['/', '\\'])
+            .filter(|part| !part.is_empty())
+            .collect();
+        let Some(file_name) = parts.pop() else {
+            return Vec::new();
+        };
+
+        let mut renamed = Vec::new();
+        let mut module = self;
+        for (depth, folder) in parts.iter().enumerate() {
+            let name = match module.folders.get(*folder) {
+                Some(name) => name.clone(),
+                None => {
+                    let base = to_module_name(folder);
+                    let mut name = base.clone();
+                    let mut suffix = 1;
+                    while module.children.contains_key(&name) {
+                        suffix += 1;
+                        name = format!(
+                            "{}_{}",
+                            base.trim_start_matches("r#").trim_end_matches('_'),
+                            suffix
+                        );
+                    }
+                    if suffix > 1 {
+                        renamed.push((parts[..=depth].join("/"), name.clone()));
+                    }
+                    module.folders.insert(folder.to_string(), name.clone());
+                    name
+                }
+            };
+            module = module.children.entry(name).or_default();
+        }
+
+        let stem = Path::new(file_name)
+            .file_stem()
+            .map_or(file_name.to_string(), |stem| {
+                stem.to_string_lossy().to_string()
+            });
+        let mut name = to_snake_case(&stem).trim_end_matches('_').to_uppercase();
+        // Reserved for the folder listings, or another file with the same stem in the folder
+        if name.is_empty()
+            || ["FILES", "ALL_FILES"].contains(&name.as_str())
+            || module.files.contains_key(&name)
+        {
+            name = to_snake_case(file_name).to_uppercase();
+        }
+        let base = name.clone();
+        let mut suffix = 1;
+        while module.files.contains_key(&name) {
+            suffix += 1;
+            name = format!("{}_{}", base, suffix);
+        }
+        module.files.insert(name, variant.to_string());
+        renamed
+    }
+
+    fn all_files(&self) -> Vec<&String> {
+        let mut all: Vec<&String> = self.files.values().collect();
+        for child in self.children.values() {
+            all.extend(child.all_files());
+        }
+        all
+    }
+
+    pub fn render(&self, depth: usize) -> String {
+        let indent = "    ".repeat(depth);
+        let mut out = format!("{}use crate::audio_files::AudioFiles;\n\n", indent);
+
+        for (name, variant) in self.files.iter() {
+            out.push_str(&format!(
+                "{}pub const {}: AudioFiles = AudioFiles::{};\n",
+                indent, name, variant
+            ));
+        }
+
+        let files: Vec<String> = self
+            .files
+            .values()
+            .map(|variant| format!("AudioFiles::{}", variant))
+            .collect();
+        let all_files: Vec<String> = self
+            .all_files()
+            .iter()
+            .map(|variant| format!("AudioFiles::{}", variant))
+            .collect();
+        out.push_str(&format!(
+            r#"
+{indent}/// Files directly in this folder
+{indent}pub const FILES: [AudioFiles; {}] = [{}];
+
+{indent}/// Files in this folder and all of its subfolders
+{indent}pub const ALL_FILES: [AudioFiles; {}] = [{}];
+
+{indent}/// Iterates over the files in this folder and all of its subfolders
+{indent}pub fn iter() -> impl Iterator<Item = AudioFiles> {{
+{indent}    ALL_FILES.into_iter()
+{indent}}}
+"#,
+            files.len(),
+            files.join(", "),
+            all_files.len(),
+            all_files.join(", "),
+            indent = indent,
+        ));
+
+        for (name, child) in self.children.iter() {
+            out.push_str(&format!(
+                "\n{indent}pub mod {} {{\n{}{indent}}}\n",
+                name,
+                child.render(depth + 1),
+                indent = indent,
+            ));
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(files: &[(&str, &str)]) -> AudioModule {
+        let mut root = AudioModule::default();
+        for (path, variant) in files {
+            root.insert(path, variant);
+        }
+        root
+    }
+
+    #[test]
+    fn folders_become_modules() {
+        let root = tree(&[
+            ("sfx/weapons/rifle_shot_01.ogg", "SfxWeaponsRifleShot01OGG"),
+            ("music/Boss Theme.ogg", "MusicBossThemeOGG"),
+        ]);
+        let weapons = &root.children["sfx"].children["weapons"];
+        assert_eq!(weapons.files["RIFLE_SHOT_01"], "SfxWeaponsRifleShot01OGG");
+        assert_eq!(
+            root.children["music"].files["BOSS_THEME"],
+            "MusicBossThemeOGG"
+        );
+        assert_eq!(root.all_files().len(), 2);
+    }
+
+    #[test]
+    fn same_stems_keep_the_extension() {
+        let root = tree(&[("hit.ogg", "HitOGG"), ("hit.wav", "HitWAV")]);
+        assert_eq!(root.files["HIT"], "HitOGG");
+        assert_eq!(root.files["HIT_WAV"], "HitWAV");
+    }
+
+    #[test]
+    fn listing_names_are_reserved() {
+        let root = tree(&[("files.ogg", "FilesOGG"), ("all_files.ogg", "AllFilesOGG")]);
+        assert_eq!(root.files["FILES_OGG"], "FilesOGG");
+        assert_eq!(root.files["ALL_FILES_OGG"], "AllFilesOGG");
+    }
+
+    #[test]
+    fn colliding_folders_get_a_suffix() {
+        let mut root = AudioModule::default();
+        assert!(root.insert("sfx-hit/a.ogg", "SfxHitAOGG").is_empty());
+        assert_eq!(
+            root.insert("sfx_hit/b.ogg", "SfxHitBOGG"),
+            [("sfx_hit".to_string(), "sfx_hit_2".to_string())]
+        );
+        // Later files in the same folder follow it without being reported again
+        assert!(root.insert("sfx_hit/c.ogg", "SfxHitCOGG").is_empty());
+
+        assert_eq!(root.children["sfx_hit"].files.len(), 1);
+        assert_eq!(root.children["sfx_hit_2"].files.len(), 2);
+    }
+
+    #[test]
+    fn keyword_folders_are_suffixed_without_the_raw_prefix() {
+        let mut root = AudioModule::default();
+        root.insert("type/a.ogg", "TypeAOGG");
+        assert_eq!(
+            root.insert("Type/b.ogg", "TypeBOGG"),
+            [("Type".to_string(), "type_2".to_string())]
+        );
+        assert!(root.render(0).contains("pub mod r#type {"));
+        assert!(root.render(0).contains("pub mod type_2 {"));
+    }
+
+    #[test]
+    fn unnamed_folders_get_a_module_name() {
+        let mut root = AudioModule::default();
+        root.insert("---/a.ogg", "AOGG");
+        assert_eq!(
+            root.insert("爆発/b.ogg", "BOGG"),
+            [("爆発".to_string(), "folder_2".to_string())]
+        );
+        let rendered = root.render(0);
+        assert!(rendered.contains("pub mod folder_ {"));
+        assert!(rendered.contains("pub mod folder_2 {"));
+        assert!(!rendered.contains("pub mod _ {"));
+    }
+}
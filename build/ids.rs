@@ -0,0 +1,33 @@
+/// The stable id of a file, the 64-bit FNV-1a hash of its path with forward slashes so it is the same on every platform
+///
+/// Must match `helpers::fnv1a` in the library, which computes the ids of files registered at runtime
+pub fn file_id(path: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    path.replace('\\', "/")
+        .bytes()
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(PRIME)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_fnv1a_test_vectors() {
+        assert_eq!(file_id(""), 0xcbf29ce484222325);
+        assert_eq!(file_id("a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(file_id("foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn separators_do_not_change_the_id() {
+        assert_eq!(
+            file_id("sfx\\weapons\\fire.ogg"),
+            file_id("sfx/weapons/fire.ogg")
+        );
+        assert_ne!(file_id("sfx/fire.ogg"), file_id("sfx/fire.wav"));
+    }
+}
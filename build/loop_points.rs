@@ -0,0 +1,182 @@
+/// Loop points of a file, in sample frames, read from tags or the `smpl` chunk of a WAV file
+#[derive(Default)]
+pub struct LoopTags {
+    start: Option<u64>,
+    end: Option<u64>,
+    length: Option<u64>,
+}
+
+impl LoopTags {
+    /// Reads a `LOOPSTART`, `LOOPEND` or `LOOPLENGTH` tag, returns false for any other tag
+    ///
+    /// Loop points are non-standard tags, e.g. the Vorbis comments used by RPG Maker and most game music tools.
+    /// The first value of a tag is kept
+    pub fn read_tag(&mut self, key: &str, value: &str) -> bool {
+        let field = match key.to_uppercase().as_str() {
+            "LOOPSTART" => &mut self.start,
+            "LOOPEND" => &mut self.end,
+            "LOOPLENGTH" => &mut self.length,
+            _ => return false,
+        };
+        if field.is_none() {
+            *field = value.trim().parse::<u64>().ok();
+        }
+        true
+    }
+
+    /// Reads the first loop from the `smpl` chunk of a WAV file, the end is stored inclusively
+    pub fn read_wav(&mut self, bytes: &[u8]) {
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return;
+        }
+        let read_u32 = |offset: usize| {
+            bytes
+                .get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+
+        let mut offset = 12;
+        while let (Some(id), Some(size)) = (bytes.get(offset..offset + 4), read_u32(offset + 4)) {
+            let data = offset + 8;
+            if id == b"smpl" {
+                // 36 bytes of sampler info precede the loop list, the loop count sits at offset 28
+                let loop_count = read_u32(data + 28).unwrap_or(0);
+                if loop_count > 0 {
+                    if let (Some(start), Some(end)) = (read_u32(data + 44), read_u32(data + 48)) {
+                        self.start = Some(start as u64);
+                        self.end = Some(end as u64 + 1);
+                    }
+                }
+                return;
+            }
+            offset = data + size as usize + (size as usize & 1);
+        }
+    }
+
+    pub fn start(&self) -> Option<u64> {
+        self.start
+    }
+
+    /// Returns the loop region, the end defaults to the end of the file and `LOOPLENGTH` is only used without a `LOOPEND`
+    ///
+    /// `None` if there is no loop start or the start is not before the end
+    pub fn resolve(&self, total_frames: u64) -> Option<(u64, u64)> {
+        let start = self.start?;
+        let end = self
+            .end
+            .or_else(|| self.length.map(|length| start + length))
+            .unwrap_or(total_frames)
+            .min(total_frames);
+        (start < end).then_some((start, end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(pairs: &[(&str, &str)]) -> LoopTags {
+        let mut tags = LoopTags::default();
+        for (key, value) in pairs {
+            tags.read_tag(key, value);
+        }
+        tags
+    }
+
+    /// A WAV file with a `smpl` chunk behind an odd sized chunk, which is padded to an even size
+    fn wav(loops: &[(u32, u32)]) -> Vec<u8> {
+        let mut smpl = vec![0u8; 36];
+        smpl[28..32].copy_from_slice(&(loops.len() as u32).to_le_bytes());
+        for (start, end) in loops {
+            // cue point id and type, then start, end, fraction and play count
+            smpl.extend_from_slice(&[0; 8]);
+            smpl.extend_from_slice(&start.to_le_bytes());
+            smpl.extend_from_slice(&end.to_le_bytes());
+            smpl.extend_from_slice(&[0; 8]);
+        }
+
+        let mut bytes = b"RIFF\0\0\0\0WAVE".to_vec();
+        bytes.extend_from_slice(b"LIST");
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(&[1, 2, 3, 0]);
+        bytes.extend_from_slice(b"smpl");
+        bytes.extend_from_slice(&(smpl.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&smpl);
+        bytes
+    }
+
+    #[test]
+    fn reads_loop_tags() {
+        let loop_tags = tags(&[("LOOPSTART", "100"), ("LOOPEND", "400")]);
+        assert_eq!(loop_tags.resolve(1_000), Some((100, 400)));
+
+        let loop_tags = tags(&[("loopstart", " 100 "), ("LoopLength", "50")]);
+        assert_eq!(loop_tags.resolve(1_000), Some((100, 150)));
+    }
+
+    #[test]
+    fn ignores_other_tags() {
+        let mut loop_tags = LoopTags::default();
+        assert!(!loop_tags.read_tag("TITLE", "100"));
+        assert!(loop_tags.read_tag("LOOPSTART", "100"));
+        assert_eq!(loop_tags.start(), Some(100));
+    }
+
+    #[test]
+    fn end_takes_priority_over_length() {
+        let loop_tags = tags(&[
+            ("LOOPSTART", "100"),
+            ("LOOPLENGTH", "50"),
+            ("LOOPEND", "300"),
+        ]);
+        assert_eq!(loop_tags.resolve(1_000), Some((100, 300)));
+    }
+
+    #[test]
+    fn first_value_is_kept() {
+        let loop_tags = tags(&[("LOOPSTART", "100"), ("LOOPSTART", "200")]);
+        assert_eq!(loop_tags.start(), Some(100));
+    }
+
+    #[test]
+    fn invalid_values_are_ignored() {
+        let loop_tags = tags(&[("LOOPSTART", "soon"), ("LOOPEND", "-5")]);
+        assert_eq!(loop_tags.resolve(1_000), None);
+    }
+
+    #[test]
+    fn end_defaults_to_and_is_clamped_to_the_file() {
+        assert_eq!(
+            tags(&[("LOOPSTART", "100")]).resolve(1_000),
+            Some((100, 1_000))
+        );
+        let loop_tags = tags(&[("LOOPSTART", "100"), ("LOOPEND", "5000")]);
+        assert_eq!(loop_tags.resolve(1_000), Some((100, 1_000)));
+    }
+
+    #[test]
+    fn rejects_empty_loops() {
+        assert_eq!(tags(&[]).resolve(1_000), None);
+        let loop_tags = tags(&[("LOOPSTART", "400"), ("LOOPEND", "400")]);
+        assert_eq!(loop_tags.resolve(1_000), None);
+        assert_eq!(tags(&[("LOOPSTART", "2000")]).resolve(1_000), None);
+    }
+
+    #[test]
+    fn reads_the_first_wav_loop() {
+        let mut loop_tags = LoopTags::default();
+        loop_tags.read_wav(&wav(&[(100, 399), (500, 599)]));
+        assert_eq!(loop_tags.resolve(1_000), Some((100, 400)));
+    }
+
+    #[test]
+    fn wav_without_loops() {
+        let mut loop_tags = LoopTags::default();
+        loop_tags.read_wav(&wav(&[]));
+        assert_eq!(loop_tags.start(), None);
+
+        loop_tags.read_wav(b"RIFF\0\0\0\0WAVE");
+        loop_tags.read_wav(b"OggS");
+        assert_eq!(loop_tags.start(), None);
+    }
+}
@@ -0,0 +1,310 @@
+const TRUE_PEAK_TAPS: usize = 12;
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+
+/// Measures integrated loudness (ITU-R BS.1770-4) and true peak of interleaved samples
+pub struct LoudnessMeter {
+    channels: usize,
+    /// Channel weights of BS.1770, the LFE channel is weighted 0.0 and left out of the loudness
+    weights: Vec<f64>,
+    filters: Vec<[Biquad; 2]>,
+    /// Samples per 100ms sub-block, four of which make up one 400ms gating block
+    sub_block_len: usize,
+    sub_block_pos: usize,
+    sub_block_power: f64,
+    sub_blocks: Vec<f64>,
+    /// Interpolation filter for every phase between two samples, computed once instead of per sample
+    peak_coefficients: [[f32; TRUE_PEAK_TAPS]; TRUE_PEAK_OVERSAMPLING - 1],
+    peak_history: Vec<[f32; TRUE_PEAK_TAPS]>,
+    peak: f32,
+}
+
+impl LoudnessMeter {
+    /// `lfe` flags the low frequency effects channels, in the interleaved order
+    pub fn new(sample_rate: u32, lfe: &[bool]) -> Self {
+        let rate = sample_rate as f64;
+        let channels = lfe.len();
+        let mut peak_coefficients = [[0.0; TRUE_PEAK_TAPS]; TRUE_PEAK_OVERSAMPLING - 1];
+        for (phase, coefficients) in peak_coefficients.iter_mut().enumerate() {
+            let offset = (phase + 1) as f32 / TRUE_PEAK_OVERSAMPLING as f32;
+            for (i, coefficient) in coefficients.iter_mut().enumerate() {
+                // distance from the interpolation point, which sits between the two middle taps
+                let x = i as f32 - (TRUE_PEAK_TAPS / 2 - 1) as f32 - offset;
+                *coefficient = windowed_sinc(x, TRUE_PEAK_TAPS as f32 / 2.0);
+            }
+        }
+        Self {
+            channels,
+            weights: lfe.iter().map(|lfe| if *lfe { 0.0 } else { 1.0 }).collect(),
+            filters: (0..channels)
+                .map(|_| [Biquad::high_shelf(rate), Biquad::high_pass(rate)])
+                .collect(),
+            sub_block_len: (sample_rate as usize / 10).max(1),
+            sub_block_pos: 0,
+            sub_block_power: 0.0,
+            sub_blocks: Vec::new(),
+            peak_coefficients,
+            peak_history: vec![[0.0; TRUE_PEAK_TAPS]; channels],
+            peak: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, interleaved: &[f32]) {
+        if self.channels == 0 {
+            return;
+        }
+        for frame in interleaved.chunks_exact(self.channels) {
+            for (channel, sample) in frame.iter().enumerate() {
+                let weight = self.weights[channel];
+                if weight > 0.0 {
+                    let [shelf, pass] = &mut self.filters[channel];
+                    let filtered = pass.process(shelf.process(*sample as f64));
+                    self.sub_block_power += weight * filtered * filtered;
+                }
+                self.track_peak(channel, *sample);
+            }
+            self.sub_block_pos += 1;
+            if self.sub_block_pos == self.sub_block_len {
+                self.sub_blocks
+                    .push(self.sub_block_power / self.sub_block_len as f64);
+                self.sub_block_pos = 0;
+                self.sub_block_power = 0.0;
+            }
+        }
+    }
+
+    /// Interpolates between samples with a windowed sinc to catch inter-sample peaks
+    fn track_peak(&mut self, channel: usize, sample: f32) {
+        let history = &mut self.peak_history[channel];
+        history.rotate_left(1);
+        history[TRUE_PEAK_TAPS - 1] = sample;
+        self.peak = self.peak.max(sample.abs());
+
+        for coefficients in self.peak_coefficients.iter() {
+            let interpolated: f32 = history
+                .iter()
+                .zip(coefficients)
+                .map(|(value, coefficient)| value * coefficient)
+                .sum();
+            self.peak = self.peak.max(interpolated.abs());
+        }
+    }
+
+    pub fn integrated_loudness(&self) -> Option<f32> {
+        // 400ms blocks with 75% overlap
+        let blocks: Vec<f64> = if self.sub_blocks.len() >= 4 {
+            self.sub_blocks
+                .windows(4)
+                .map(|window| window.iter().sum::<f64>() / 4.0)
+                .collect()
+        } else if !self.sub_blocks.is_empty() || self.sub_block_pos > 0 {
+            // Shorter than a single block, measure whatever is there
+            let total: f64 = self
+                .sub_blocks
+                .iter()
+                .map(|power| power * self.sub_block_len as f64)
+                .sum::<f64>()
+                + self.sub_block_power;
+            let len = self.sub_blocks.len() * self.sub_block_len + self.sub_block_pos;
+            vec![total / len as f64]
+        } else {
+            return None;
+        };
+
+        let absolute: Vec<f64> = blocks
+            .into_iter()
+            .filter(|power| block_loudness(*power) > -70.0)
+            .collect();
+        if absolute.is_empty() {
+            return None;
+        }
+        let relative_gate = block_loudness(mean(&absolute)) - 10.0;
+        let relative: Vec<f64> = absolute
+            .into_iter()
+            .filter(|power| block_loudness(*power) > relative_gate)
+            .collect();
+        if relative.is_empty() {
+            return None;
+        }
+
+        Some(block_loudness(mean(&relative)) as f32)
+    }
+
+    pub fn true_peak(&self) -> Option<f32> {
+        if self.peak > 0.0 {
+            Some(20.0 * self.peak.log10())
+        } else {
+            None
+        }
+    }
+}
+
+fn block_loudness(power: f64) -> f64 {
+    -0.691 + 10.0 * power.log10()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn windowed_sinc(x: f32, half_width: f32) -> f32 {
+    if x == 0.0 {
+        return 1.0;
+    }
+    if x.abs() >= half_width {
+        return 0.0;
+    }
+    let pi_x = std::f32::consts::PI * x;
+    let hann = 0.5 * (1.0 + (pi_x / half_width).cos());
+    pi_x.sin() / pi_x * hann
+}
+
+/// Second order IIR filter used for the K-weighting stages
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    z: [f64; 2],
+}
+
+impl Biquad {
+    /// Stage one of the K-weighting filter, models the acoustic effect of the head
+    fn high_shelf(rate: f64) -> Self {
+        let f0 = 1681.974450955533;
+        let gain = 3.999843853973347;
+        let q = 0.7071752369554196;
+
+        let k = (std::f64::consts::PI * f0 / rate).tan();
+        let vh = 10f64.powf(gain / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+        Self {
+            b: [
+                (vh + vb * k / q + k * k) / a0,
+                2.0 * (k * k - vh) / a0,
+                (vh - vb * k / q + k * k) / a0,
+            ],
+            a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            z: [0.0; 2],
+        }
+    }
+
+    /// Stage two of the K-weighting filter, the RLB high pass
+    fn high_pass(rate: f64) -> Self {
+        let f0 = 38.13547087602444;
+        let q = 0.5003270373238773;
+
+        let k = (std::f64::consts::PI * f0 / rate).tan();
+        let a0 = 1.0 + k / q + k * k;
+        Self {
+            b: [1.0, -2.0, 1.0],
+            a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            z: [0.0; 2],
+        }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        let output = self.b[0] * input + self.z[0];
+        self.z[0] = self.b[1] * input - self.a[0] * output + self.z[1];
+        self.z[1] = self.b[2] * input - self.a[1] * output;
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 48_000;
+
+    fn sine(frequency: f32, amplitude: f32, seconds: f32, phase: f32) -> Vec<f32> {
+        let len = (seconds * RATE as f32) as usize;
+        (0..len)
+            .map(|i| {
+                let t = i as f64 / RATE as f64;
+                let angle = std::f64::consts::TAU * frequency as f64 * t + phase as f64;
+                amplitude * angle.sin() as f32
+            })
+            .collect()
+    }
+
+    fn measure(lfe: &[bool], interleaved: &[f32]) -> LoudnessMeter {
+        let mut meter = LoudnessMeter::new(RATE, lfe);
+        meter.process(interleaved);
+        meter
+    }
+
+    #[test]
+    fn sine_at_minus_20_dbfs_is_minus_23_lufs() {
+        // A 1kHz sine is barely changed by the K-weighting, so it measures 3dB below its peak level
+        let loudness = measure(&[false], &sine(1_000.0, 0.1, 5.0, 0.0))
+            .integrated_loudness()
+            .unwrap();
+        assert!((loudness + 23.0).abs() < 0.1, "{}", loudness);
+    }
+
+    #[test]
+    fn silence_has_no_loudness() {
+        let meter = measure(&[false], &vec![0.0; RATE as usize]);
+        assert_eq!(meter.integrated_loudness(), None);
+        assert_eq!(meter.true_peak(), None);
+        assert_eq!(
+            LoudnessMeter::new(RATE, &[false]).integrated_loudness(),
+            None
+        );
+    }
+
+    #[test]
+    fn quiet_blocks_are_gated() {
+        let mut samples = sine(1_000.0, 0.1, 5.0, 0.0);
+        // 30dB quieter, below the relative gate of -10 LU
+        samples.extend(sine(1_000.0, 0.003, 5.0, 0.0));
+        let loudness = measure(&[false], &samples).integrated_loudness().unwrap();
+        assert!((loudness + 23.0).abs() < 0.2, "{}", loudness);
+
+        // Below the absolute gate of -70 LUFS
+        let quiet = measure(&[false], &sine(1_000.0, 0.0001, 2.0, 0.0));
+        assert_eq!(quiet.integrated_loudness(), None);
+    }
+
+    #[test]
+    fn short_files_are_measured() {
+        let loudness = measure(&[false], &sine(1_000.0, 0.1, 0.2, 0.0))
+            .integrated_loudness()
+            .unwrap();
+        assert!((loudness + 23.0).abs() < 0.5, "{}", loudness);
+    }
+
+    #[test]
+    fn lfe_is_left_out_of_the_loudness() {
+        let tone = sine(1_000.0, 0.1, 5.0, 0.0);
+        let rumble = sine(60.0, 0.9, 5.0, 0.0);
+        let interleaved: Vec<f32> = tone
+            .iter()
+            .zip(rumble.iter())
+            .flat_map(|(tone, rumble)| [*tone, *rumble])
+            .collect();
+        let meter = measure(&[false, true], &interleaved);
+        let loudness = meter.integrated_loudness().unwrap();
+        assert!((loudness + 23.0).abs() < 0.1, "{}", loudness);
+        // The peak still covers every channel
+        assert!(meter.true_peak().unwrap() > -1.0);
+    }
+
+    #[test]
+    fn true_peak_finds_inter_sample_peaks() {
+        // A quarter of the sample rate, offset by 45 degrees, never samples its peak
+        let samples = sine(RATE as f32 / 4.0, 0.5, 1.0, std::f32::consts::FRAC_PI_4);
+        let sample_peak = 20.0 * samples.iter().fold(0f32, |a, s| a.max(s.abs())).log10();
+        let true_peak = measure(&[false], &samples).true_peak().unwrap();
+        assert!((sample_peak + 9.03).abs() < 0.1, "{}", sample_peak);
+        assert!((true_peak + 6.02).abs() < 0.5, "{}", true_peak);
+    }
+
+    #[test]
+    fn windowed_sinc_is_zero_at_integer_taps() {
+        assert_eq!(windowed_sinc(0.0, 6.0), 1.0);
+        for x in 1..6 {
+            assert!(windowed_sinc(x as f32, 6.0).abs() < 1e-6);
+        }
+        assert_eq!(windowed_sinc(6.0, 6.0), 0.0);
+    }
+}
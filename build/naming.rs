@@ -0,0 +1,262 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// Enum variant and field names picked by [assign_identifiers]
+pub struct Identifiers {
+    /// Enum variant and field name of every path, in the order of the paths
+    pub names: Vec<(String, String)>,
+    /// Paths that had to be sanitized to be used as identifiers
+    pub sanitized: Vec<usize>,
+    /// Paths that map to the same identifier, the first one keeps it and the others get a numeric suffix
+    pub collisions: Vec<Vec<usize>>,
+}
+
+/// Picks the enum variant and field names for every path
+///
+/// Paths that can not be used as identifiers as is are sanitized, and paths that end up with the same name
+/// as another path get a numeric suffix in path order so the result does not depend on the file system
+pub fn assign_identifiers(paths: &[String]) -> Identifiers {
+    let mut order: Vec<usize> = (0..paths.len()).collect();
+    order.sort_by(|a, b| paths[*a].cmp(&paths[*b]));
+
+    let mut names = vec![(String::new(), String::new()); paths.len()];
+    let mut sanitized = Vec::new();
+    let mut taken_variants = HashMap::<String, usize>::new();
+    let mut taken_fields = HashMap::<String, usize>::new();
+    let mut collisions = BTreeMap::<String, Vec<usize>>::new();
+
+    for index in order {
+        let path = &paths[index];
+        let variant = to_pascal_case(path);
+        let field = to_snake_case(path);
+
+        if path.contains(|c: char| {
+            !(c.is_ascii_alphanumeric() || "-_./\\".contains(c) || c == std::path::MAIN_SEPARATOR)
+        }) || path.starts_with(|c: char| c.is_ascii_digit())
+        {
+            sanitized.push(index);
+        }
+
+        let mut suffix = 1;
+        let (mut unique_variant, mut unique_field) = (variant.clone(), field.clone());
+        while taken_variants.contains_key(&unique_variant)
+            || taken_fields.contains_key(&unique_field)
+        {
+            if suffix == 1 {
+                let owner = taken_variants
+                    .get(&unique_variant)
+                    .or_else(|| taken_fields.get(&unique_field))
+                    .copied()
+                    .unwrap();
+                collisions
+                    .entry(variant.clone())
+                    .or_insert_with(|| vec![owner])
+                    .push(index);
+            }
+            suffix += 1;
+            unique_variant = format!("{}{}", variant, suffix);
+            unique_field = format!("{}_{}", field, suffix);
+        }
+
+        taken_variants.insert(unique_variant.clone(), index);
+        taken_fields.insert(unique_field.clone(), index);
+        names[index] = (unique_variant, unique_field);
+    }
+
+    Identifiers {
+        names,
+        sanitized,
+        collisions: collisions.into_values().collect(),
+    }
+}
+
+pub fn to_module_name(folder: &str) -> String {
+    let name = to_snake_case(folder);
+    match name.as_str() {
+        // `_` is not a valid module name
+        _ if name.chars().all(|c| c == '_') => "folder_".to_string(),
+        "self" | "super" | "crate" => format!("{}_", name),
+        "as" | "async" | "await" | "break" | "const" | "continue" | "dyn" | "else" | "enum"
+        | "extern" | "false" | "fn" | "for" | "if" | "impl" | "in" | "let" | "loop" | "match"
+        | "mod" | "move" | "mut" | "pub" | "ref" | "return" | "static" | "struct" | "trait"
+        | "true" | "type" | "unsafe" | "use" | "where" | "while" | "abstract" | "become"
+        | "box" | "do" | "final" | "gen" | "macro" | "override" | "priv" | "try" | "typeof"
+        | "unsized" | "virtual" | "yield" => format!("r#{}", name),
+        _ => name,
+    }
+}
+
+pub fn to_pascal_case(path: &str) -> String {
+    let mut parts: Vec<String> = transliterate(path)
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let mut chars = s.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    if let Some(extension) = parts.last_mut() {
+        *extension = extension.to_uppercase();
+    }
+
+    let pascal_case = parts.concat();
+    if pascal_case.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("File{}", pascal_case)
+    } else {
+        pascal_case
+    }
+}
+
+pub fn to_snake_case(path: &str) -> String {
+    let mut snake_case = String::new();
+    let name = transliterate(path).replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    let mut prev_char = '\0';
+    for (i, ch) in name.chars().enumerate() {
+        // Runs of separators would trip the `non_snake_case` lint
+        if ch == '_' && prev_char == '_' {
+            continue;
+        }
+        if ch.is_uppercase() && i > 0 && prev_char != '_' {
+            snake_case.push('_');
+        }
+        snake_case.push(ch.to_ascii_lowercase());
+        prev_char = ch;
+    }
+    if snake_case.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("file_{}", snake_case)
+    } else {
+        snake_case
+    }
+}
+
+/// Replaces accented latin characters with their closest ASCII equivalent,
+/// anything else that is not ASCII is left for the identifier functions to strip
+fn transliterate(path: &str) -> String {
+    let mut transliterated = String::with_capacity(path.len());
+    for ch in path.chars() {
+        let replacement = match ch {
+            'À'..='Å' => "A",
+            'à'..='å' => "a",
+            'Æ' => "AE",
+            'æ' => "ae",
+            'Ç' => "C",
+            'ç' => "c",
+            'È'..='Ë' => "E",
+            'è'..='ë' => "e",
+            'Ì'..='Ï' => "I",
+            'ì'..='ï' => "i",
+            'Ð' => "D",
+            'ð' => "d",
+            'Ñ' => "N",
+            'ñ' => "n",
+            'Ò'..='Ö' | 'Ø' => "O",
+            'ò'..='ö' | 'ø' => "o",
+            'Œ' => "OE",
+            'œ' => "oe",
+            'Ù'..='Ü' => "U",
+            'ù'..='ü' => "u",
+            'Ý' | 'Ÿ' => "Y",
+            'ý' | 'ÿ' => "y",
+            'Þ' => "TH",
+            'þ' => "th",
+            'ß' => "ss",
+            _ => {
+                transliterated.push(ch);
+                continue;
+            }
+        };
+        transliterated.push_str(replacement);
+    }
+    transliterated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assign(paths: &[&str]) -> Identifiers {
+        assign_identifiers(
+            &paths
+                .iter()
+                .map(|path| path.to_string())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn pascal_case_keeps_the_extension() {
+        assert_eq!(to_pascal_case("sfx/fire.ogg"), "SfxFireOGG");
+        assert_eq!(
+            to_pascal_case("music/boss-theme_02.wav"),
+            "MusicBossTheme02WAV"
+        );
+        assert_eq!(to_pascal_case("01 intro.mp3"), "File01IntroMP3");
+    }
+
+    #[test]
+    fn snake_case_collapses_separators() {
+        assert_eq!(to_snake_case("sfx/fire.ogg"), "sfx_fire_ogg");
+        assert_eq!(to_snake_case("sfx//big  Boom.ogg"), "sfx_big_boom_ogg");
+        assert_eq!(to_snake_case("01 intro.mp3"), "file_01_intro_mp3");
+    }
+
+    #[test]
+    fn accents_are_transliterated() {
+        assert_eq!(transliterate("Café Ærø straße"), "Cafe AEro strasse");
+        assert_eq!(to_pascal_case("música/canción.ogg"), "MusicaCancionOGG");
+        // Anything else that is not ASCII is stripped
+        assert_eq!(to_snake_case("sfx/爆発.ogg"), "sfx_ogg");
+    }
+
+    #[test]
+    fn module_names_avoid_keywords() {
+        assert_eq!(to_module_name("Weapons"), "weapons");
+        assert_eq!(to_module_name("type"), "r#type");
+        assert_eq!(to_module_name("self"), "self_");
+        assert_eq!(to_module_name("---"), "folder_");
+        assert_eq!(to_module_name("爆発"), "folder_");
+    }
+
+    #[test]
+    fn valid_paths_are_used_as_is() {
+        let identifiers = assign(&["sfx/fire.ogg", "music/theme.ogg"]);
+        assert_eq!(
+            identifiers.names,
+            [
+                ("SfxFireOGG".to_string(), "sfx_fire_ogg".to_string()),
+                ("MusicThemeOGG".to_string(), "music_theme_ogg".to_string()),
+            ]
+        );
+        assert!(identifiers.sanitized.is_empty());
+        assert!(identifiers.collisions.is_empty());
+    }
+
+    #[test]
+    fn invalid_paths_are_reported() {
+        let identifiers = assign(&["sfx/fire.ogg", "sfx/big boom.ogg", "1up.ogg"]);
+        assert_eq!(identifiers.sanitized, [2, 1]);
+    }
+
+    #[test]
+    fn collisions_get_a_suffix_in_path_order() {
+        let identifiers = assign(&["sfx_hit.ogg", "sfx-hit.ogg", "sfx/hit.ogg"]);
+        assert_eq!(identifiers.names[1].0, "SfxHitOGG");
+        assert_eq!(identifiers.names[2].0, "SfxHitOGG2");
+        assert_eq!(identifiers.names[0].0, "SfxHitOGG3");
+        assert_eq!(identifiers.names[0].1, "sfx_hit_ogg_3");
+        assert_eq!(identifiers.collisions, [vec![1, 2, 0]]);
+    }
+
+    #[test]
+    fn suffixes_skip_taken_names() {
+        let identifiers = assign(&["hit.ogg", "hit_ogg.ogg", "hit-ogg.ogg", "hitOGG2.ogg"]);
+        let variants: Vec<&str> = identifiers.names.iter().map(|(v, _)| v.as_str()).collect();
+        let mut unique = variants.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), variants.len(), "{:?}", variants);
+    }
+}
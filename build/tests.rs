@@ -0,0 +1,8 @@
+//! Build scripts can not have tests, so the unit tests of its helper modules run in this target instead
+#![allow(dead_code)]
+
+mod audio_module;
+mod ids;
+mod loop_points;
+mod loudness;
+mod naming;
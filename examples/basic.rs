@@ -16,7 +16,7 @@ fn main() {
             ..Default::default()
         }))
         .add_plugins(WorldInspectorPlugin::new())
-        .add_plugins(AudioControllerPlugin)
+        .add_plugins(AudioControllerPlugin::default())
         .add_systems(Startup, setup)
         .add_systems(
             Update,
@@ -30,7 +30,7 @@ fn main() {
 }
 
 fn setup(mut commands: Commands) {
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
     commands
         .spawn(helpers::get_container())
         .with_children(|parent| {
@@ -45,7 +45,7 @@ fn play_with_plugin(mut sfx_play_ew: EventWriter<GlobalPlayEvent>) {
     let event = GlobalPlayEvent::new(AudioFiles::FireOGG).with_settings(PlaybackSettings::DESPAWN);
     sfx_play_ew.send(event);
     // You can send events using the enum values or a string
-    // sfx_play_ew.send(GlobalPlayEvent::new("fire.ogg".into()));
+    // sfx_play_ew.send(GlobalPlayEvent::new("fire.ogg".parse().unwrap()));
 }
 
 fn play_without_plugin(mut commands: Commands, asset_server: Res<AssetServer>) {
@@ -3,14 +3,10 @@ use bevy_inspector_egui::quick::WorldInspectorPlugin;
 
 use bevy_audio_controller::prelude::*;
 
-#[derive(Component, Default, AudioChannel)]
-#[cfg_attr(feature = "inspect", derive(Reflect))]
-#[cfg_attr(feature = "inspect", reflect(Component))]
+#[audio_channel_marker]
 struct MusicChannel;
 
-#[derive(Component, Default, AudioChannel)]
-#[cfg_attr(feature = "inspect", derive(Reflect))]
-#[cfg_attr(feature = "inspect", reflect(Component))]
+#[audio_channel_marker]
 struct SfxChannel;
 
 fn main() {
@@ -20,7 +16,7 @@ fn main() {
             ..Default::default()
         }))
         .add_plugins(WorldInspectorPlugin::new())
-        .add_plugins(AudioControllerPlugin)
+        .add_plugins(AudioControllerPlugin::default())
         .register_audio_channel::<MusicChannel>()
         .register_audio_channel::<SfxChannel>()
         .add_systems(Startup, setup)
@@ -29,9 +25,9 @@ fn main() {
 }
 
 fn setup(mut commands: Commands, mut ew: EventWriter<PlayEvent<MusicChannel>>) {
-    commands.spawn(Camera2d::default());
-    let event =
-        MusicChannel::play_event("background.ogg".into()).with_settings(PlaybackSettings::LOOP);
+    commands.spawn(Camera2d);
+    let event = MusicChannel::play_event("background.ogg".parse().unwrap())
+        .with_settings(PlaybackSettings::LOOP);
     ew.send(event);
 }
 
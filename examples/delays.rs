@@ -5,9 +5,7 @@ use bevy_audio_controller::prelude::*;
 
 mod helpers;
 
-#[derive(Component, Default, AudioChannel)]
-#[cfg_attr(feature = "inspect", derive(Reflect))]
-#[cfg_attr(feature = "inspect", reflect(Component))]
+#[audio_channel_marker]
 struct SfxChannel;
 
 fn main() {
@@ -17,7 +15,7 @@ fn main() {
             ..Default::default()
         }))
         .add_plugins(WorldInspectorPlugin::new())
-        .add_plugins(AudioControllerPlugin)
+        .add_plugins(AudioControllerPlugin::default())
         .register_audio_channel::<SfxChannel>()
         .add_systems(Startup, (setup,))
         .add_systems(Update, (play_sfx,))
@@ -25,7 +23,7 @@ fn main() {
 }
 
 fn setup(mut commands: Commands) {
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
     commands
         .spawn(helpers::get_container())
         .with_children(|parent| {
@@ -9,9 +9,7 @@ use bevy_audio_controller::prelude::*;
 
 mod helpers;
 
-#[derive(Component, Default, AudioChannel)]
-#[cfg_attr(feature = "inspect", derive(Reflect))]
-#[cfg_attr(feature = "inspect", reflect(Component))]
+#[audio_channel_marker]
 struct FireChannel;
 
 fn main() {
@@ -21,7 +19,7 @@ fn main() {
             ..Default::default()
         }))
         .add_plugins(WorldInspectorPlugin::new())
-        .add_plugins(AudioControllerPlugin)
+        .add_plugins(AudioControllerPlugin::default())
         .register_audio_channel::<FireChannel>()
         .add_systems(Startup, setup)
         .add_systems(
@@ -36,7 +34,7 @@ fn main() {
 }
 
 fn setup(mut commands: Commands, mut settings: ResMut<ChannelSettings<GlobalChannel>>) {
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
     commands
         .spawn(helpers::get_container())
         .with_children(|parent| {
@@ -5,9 +5,7 @@ use bevy_audio_controller::prelude::*;
 
 mod helpers;
 
-#[derive(Component, Default, AudioChannel)]
-#[cfg_attr(feature = "inspect", derive(Reflect))]
-#[cfg_attr(feature = "inspect", reflect(Component))]
+#[audio_channel_marker]
 struct SfxChannel;
 
 #[derive(Component)]
@@ -23,7 +21,7 @@ fn main() {
             ..Default::default()
         }))
         .add_plugins(WorldInspectorPlugin::new())
-        .add_plugins(AudioControllerPlugin)
+        .add_plugins(AudioControllerPlugin::default())
         .register_audio_channel::<SfxChannel>()
         .add_systems(Startup, (setup, set_channel_settings))
         .add_systems(
@@ -39,7 +37,7 @@ fn main() {
 fn setup(mut commands: Commands) {
     commands.spawn((Name::new("SFX Container"), SfxParent));
     commands.spawn((Name::new("Player"), Player));
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
 
     commands
         .spawn(helpers::get_container())
@@ -96,7 +94,7 @@ fn play_sfx(
             .as_child(),
     );
     ew.send(
-        SfxChannel::play_event("spray.ogg".into())
+        SfxChannel::play_event("spray.ogg".parse().unwrap())
             .with_settings(PlaybackSettings::REMOVE)
             .with_entity(player_entity),
     );
@@ -3,9 +3,7 @@ use bevy_inspector_egui::quick::WorldInspectorPlugin;
 
 use bevy_audio_controller::prelude::*;
 
-#[derive(Component, Default, AudioChannel)]
-#[cfg_attr(feature = "inspect", derive(Reflect))]
-#[cfg_attr(feature = "inspect", reflect(Component))]
+#[audio_channel_marker]
 struct SfxChannel;
 
 fn main() {
@@ -15,7 +13,7 @@ fn main() {
             ..Default::default()
         }))
         .add_plugins(WorldInspectorPlugin::new())
-        .add_plugins(AudioControllerPlugin)
+        .add_plugins(AudioControllerPlugin::default())
         .register_audio_channel::<SfxChannel>()
         .add_systems(Startup, setup)
         .add_systems(Update, (play_sfx,))
@@ -24,7 +22,7 @@ fn main() {
 }
 
 fn setup(mut commands: Commands) {
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
 }
 
 fn play_sfx(mut commands: Commands) {
@@ -1,32 +1,329 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
+use syn::{
+    punctuated::Punctuated, spanned::Spanned, Expr, ExprLit, Lit, LitStr, Path, Token, UnOp,
+};
+
+const SETTINGS: &[&str] = &["ONCE", "LOOP", "DESPAWN", "REMOVE"];
 
 /// Derive macro for adding convenient event constructors to an audio channel.
-#[proc_macro_derive(AudioChannel)]
+///
+/// It also adds `PlayEvent` and `SettingsEvent` type aliases named after the channel, `SfxChannel` gets `SfxPlayEvent` and `SfxSettingsEvent`.
+///
+/// The channel settings can be seeded with the `audio_channel` attribute, they are applied when the channel is registered:
+/// ```ignore
+/// #[derive(Component, Default, AudioChannel)]
+/// #[audio_channel(volume = 0.6, delay_mode = Immediate, settings = DESPAWN, name = "Sound Effects", parent = EffectsChannel)]
+/// struct SfxChannel;
+/// ```
+/// - `volume`: the channel volume, a number of at least 0.0
+/// - `delay_mode`: `Wait`, `Immediate`, `Percent(u16)` or `Milliseconds(i16)`
+/// - `settings`: the default `PlaybackSettings`, one of `ONCE`, `LOOP`, `DESPAWN` or `REMOVE`
+/// - `name`: the name shown in the `AudioChannelRegistry`
+/// - `parent`: a channel whose volume and mute state this channel follows
+#[proc_macro_derive(AudioChannel, attributes(audio_channel))]
 pub fn derive_channel(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast: syn::DeriveInput = syn::parse(input).unwrap();
     let name = &ast.ident;
+    let vis = &ast.vis;
+
+    let attrs = match ChannelAttrs::parse(&ast) {
+        Ok(attrs) => attrs,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let name_string = name.to_string();
+    let prefix = match name_string.strip_suffix("Channel") {
+        Some(prefix) if !prefix.is_empty() => prefix,
+        _ => &name_string,
+    };
+    let play_type_name = format_ident!("{}PlayEvent", prefix);
+    let settings_type_name = format_ident!("{}SettingsEvent", prefix);
+
+    let display_name = attrs.name.map(|display_name| {
+        quote! {
+            const NAME: Option<&'static str> = Some(#display_name);
+        }
+    });
+
+    let mut configure = Vec::new();
+    if let Some(volume) = attrs.volume {
+        configure.push(quote! { settings.set_channel_volume(#volume); });
+    }
+    if let Some(delay_mode) = attrs.delay_mode {
+        configure.push(quote! { settings.set_default_delay_mode(#delay_mode); });
+    }
+    if let Some(playback) = attrs.settings {
+        configure.push(quote! {
+            settings.set_default_settings(bevy::audio::PlaybackSettings::#playback);
+        });
+    }
+    let configure = (!configure.is_empty()).then(|| {
+        quote! {
+            fn configure(settings: &mut bevy_audio_controller::prelude::ChannelSettings<Self>) {
+                #(#configure)*
+            }
+        }
+    });
 
-    // let play_type_name = format_ident!("{}PlayEvent", name);
-    // let track_type_name = format_ident!("{}TrackEvent", name);
-    // let volume_type_name = format_ident!("{}VolumeEvent", name);
-    // let default_type_name = format_ident!("{}DefaultSettingsEvent", name);
+    let parent = attrs.parent.map(|parent| {
+        quote! {
+            fn register_parent(app: &mut bevy::app::App) {
+                bevy_audio_controller::prelude::ChannelRegistration::set_audio_channel_parent::<Self, #parent>(app);
+            }
+        }
+    });
 
     let expanded = quote! {
-        // pub type #play_type_name = PlayEvent<#name>;
-        // pub type #track_type_name = TrackEvent<#name>;
-        // pub type #volume_type_name = VolumeEvent<#name>;
-        // pub type #default_type_name = DefaultSettingsEvent<#name>;
+        /// Type alias for the PlayEvent with the
+        #[doc = concat!("[", stringify!(#name), "]")]
+        #[allow(dead_code)]
+        #vis type #play_type_name = bevy_audio_controller::prelude::PlayEvent<#name>;
+
+        /// Type alias for the SettingsEvent with the
+        #[doc = concat!("[", stringify!(#name), "]")]
+        #[allow(dead_code)]
+        #vis type #settings_type_name = bevy_audio_controller::prelude::SettingsEvent<#name>;
+
+        impl bevy_audio_controller::prelude::AudioChannel for #name {
+            #display_name
 
-        impl AudioChannel for #name {
             fn play_event(id: bevy_audio_controller::audio_files::AudioFiles) -> bevy_audio_controller::prelude::PlayEvent<#name> {
                 bevy_audio_controller::prelude::PlayEvent::new(id)
             }
             fn settings_event() -> bevy_audio_controller::prelude::SettingsEvent<#name> {
                 bevy_audio_controller::prelude::SettingsEvent::new()
             }
+
+            #configure
+            #parent
         }
     };
 
     TokenStream::from(expanded)
 }
+
+/// Attribute macro that turns a struct into an audio channel without the usual boilerplate
+///
+/// Derives `Component`, `Default` and `AudioChannel`, traits that are already derived are skipped.
+/// The arguments are the same as the ones of `#[audio_channel(..)]`.
+///
+/// `Reflect` is derived as well when the `inspect` feature of `bevy_audio_controller` is enabled, which turns on the
+/// feature of the same name in this crate. `ReflectComponent` is registered by `register_audio_channel`,
+/// so the channel does not need `#[reflect(Component)]` or any reflect imports in scope.
+/// ```ignore
+/// #[audio_channel_marker(volume = 0.6, delay_mode = Immediate)]
+/// struct SfxChannel;
+///
+/// fn play_fire(mut ew: EventWriter<SfxPlayEvent>) {
+///     ew.send(SfxChannel::play_event(AudioFiles::FireOGG));
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn audio_channel_marker(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = proc_macro2::TokenStream::from(args);
+    let ast: syn::DeriveInput = match syn::parse(input) {
+        Ok(ast) => ast,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let derived = match derived_traits(&ast.attrs) {
+        Ok(derived) => derived,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let is_derived = |name: &str| derived.iter().any(|derived| derived == name);
+
+    let mut derives = Vec::new();
+    if !is_derived("Component") {
+        derives.push(quote! { bevy::ecs::component::Component });
+    }
+    if !is_derived("Default") {
+        derives.push(quote! { ::core::default::Default });
+    }
+    if !is_derived("AudioChannel") {
+        derives.push(quote! { bevy_audio_controller::prelude::AudioChannel });
+    }
+    let derives = (!derives.is_empty()).then(|| quote! { #[derive(#(#derives),*)] });
+
+    // The feature is set by `bevy_audio_controller/inspect`, the library needs `Reflect` on channels with it
+    let reflect = (cfg!(feature = "inspect") && !is_derived("Reflect")).then(|| {
+        quote! { #[derive(bevy::reflect::Reflect)] }
+    });
+
+    let helper = (!args.is_empty()).then(|| quote! { #[audio_channel(#args)] });
+
+    TokenStream::from(quote! {
+        #derives
+        #reflect
+        #helper
+        #ast
+    })
+}
+
+/// Returns the names of the traits in the `#[derive(..)]` attributes
+fn derived_traits(attrs: &[syn::Attribute]) -> syn::Result<Vec<String>> {
+    let mut derived = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("derive")) {
+        let paths = attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+        derived.extend(paths.iter().filter_map(last_segment));
+    }
+    Ok(derived)
+}
+
+#[derive(Default)]
+struct ChannelAttrs {
+    volume: Option<f32>,
+    delay_mode: Option<proc_macro2::TokenStream>,
+    settings: Option<syn::Ident>,
+    name: Option<LitStr>,
+    parent: Option<Path>,
+}
+
+impl ChannelAttrs {
+    fn parse(ast: &syn::DeriveInput) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+        for attr in ast.attrs.iter() {
+            if !attr.path().is_ident("audio_channel") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                let key = meta
+                    .path
+                    .get_ident()
+                    .map(|ident| ident.to_string())
+                    .unwrap_or_default();
+                let duplicate = match key.as_str() {
+                    "volume" => attrs.volume.is_some(),
+                    "delay_mode" => attrs.delay_mode.is_some(),
+                    "settings" => attrs.settings.is_some(),
+                    "name" => attrs.name.is_some(),
+                    "parent" => attrs.parent.is_some(),
+                    _ => {
+                        return Err(meta.error(
+                            "unknown audio_channel attribute, expected one of `volume`, `delay_mode`, `settings`, `name` or `parent`",
+                        ))
+                    }
+                };
+                if duplicate {
+                    return Err(meta.error(format!("`{}` is set more than once", key)));
+                }
+                let value = meta.value()?;
+                match key.as_str() {
+                    "volume" => attrs.volume = Some(parse_volume(&value.parse()?)?),
+                    "delay_mode" => attrs.delay_mode = Some(parse_delay_mode(&value.parse()?)?),
+                    "settings" => attrs.settings = Some(parse_settings(&value.parse()?)?),
+                    "name" => {
+                        let name: LitStr = value.parse()?;
+                        if name.value().trim().is_empty() {
+                            return Err(syn::Error::new(name.span(), "`name` can not be empty"));
+                        }
+                        attrs.name = Some(name);
+                    }
+                    _ => {
+                        let parent: Path = value.parse()?;
+                        if parent.is_ident(&ast.ident) {
+                            return Err(syn::Error::new(
+                                parent.span(),
+                                "a channel can not be its own parent",
+                            ));
+                        }
+                        attrs.parent = Some(parent);
+                    }
+                }
+                Ok(())
+            })?;
+        }
+        Ok(attrs)
+    }
+}
+
+fn parse_volume(expr: &Expr) -> syn::Result<f32> {
+    let volume = match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Float(lit),
+            ..
+        }) => lit.base10_parse::<f32>()?,
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse::<f32>()?,
+        _ => {
+            return Err(syn::Error::new(
+                expr.span(),
+                "`volume` must be a number of at least 0.0, e.g. `volume = 0.6`",
+            ))
+        }
+    };
+    if !volume.is_finite() {
+        return Err(syn::Error::new(expr.span(), "`volume` must be finite"));
+    }
+    Ok(volume)
+}
+
+fn parse_delay_mode(expr: &Expr) -> syn::Result<proc_macro2::TokenStream> {
+    const EXPECTED: &str =
+        "`delay_mode` must be one of `Wait`, `Immediate`, `Percent(u16)` or `Milliseconds(i16)`";
+    let delay_mode = quote! { bevy_audio_controller::prelude::DelayMode };
+    match expr {
+        Expr::Path(path) => match last_segment(&path.path).as_deref() {
+            Some("Wait") => Ok(quote! { #delay_mode::Wait }),
+            Some("Immediate") => Ok(quote! { #delay_mode::Immediate }),
+            _ => Err(syn::Error::new(expr.span(), EXPECTED)),
+        },
+        Expr::Call(call) if call.args.len() == 1 => {
+            let Expr::Path(path) = call.func.as_ref() else {
+                return Err(syn::Error::new(expr.span(), EXPECTED));
+            };
+            let arg = &call.args[0];
+            match last_segment(&path.path).as_deref() {
+                Some("Percent") => {
+                    let percent = parse_int(arg)?;
+                    let percent = u16::try_from(percent).map_err(|_| {
+                        syn::Error::new(arg.span(), "`Percent` must be between 0 and 65535")
+                    })?;
+                    Ok(quote! { #delay_mode::Percent(#percent) })
+                }
+                Some("Milliseconds") => {
+                    let milliseconds = parse_int(arg)?;
+                    let milliseconds = i16::try_from(milliseconds).map_err(|_| {
+                        syn::Error::new(
+                            arg.span(),
+                            "`Milliseconds` must be between -32768 and 32767",
+                        )
+                    })?;
+                    Ok(quote! { #delay_mode::Milliseconds(#milliseconds) })
+                }
+                _ => Err(syn::Error::new(expr.span(), EXPECTED)),
+            }
+        }
+        _ => Err(syn::Error::new(expr.span(), EXPECTED)),
+    }
+}
+
+fn parse_settings(expr: &Expr) -> syn::Result<syn::Ident> {
+    if let Expr::Path(path) = expr {
+        if let Some(segment) = path.path.segments.last() {
+            if SETTINGS.contains(&segment.ident.to_string().as_str()) {
+                return Ok(segment.ident.clone());
+            }
+        }
+    }
+    Err(syn::Error::new(
+        expr.span(),
+        "`settings` must be one of `ONCE`, `LOOP`, `DESPAWN` or `REMOVE`",
+    ))
+}
+
+fn parse_int(expr: &Expr) -> syn::Result<i64> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse(),
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => Ok(-parse_int(&unary.expr)?),
+        _ => Err(syn::Error::new(expr.span(), "expected an integer")),
+    }
+}
+
+fn last_segment(path: &Path) -> Option<String> {
+    path.segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+}
@@ -1,16 +1,36 @@
-pub use bevy_audio_controller_derive::AudioChannel;
+pub use bevy_audio_controller_derive::{audio_channel_marker, AudioChannel};
+
+use bevy::app::App;
 
 use crate::{
     audio_files::AudioFiles,
     bounds::ACBounds,
     events::{PlayEvent, SettingsEvent},
+    resources::ChannelSettings,
 };
 
 pub trait AudioChannel {
+    /// The name the channel is shown with in the [crate::registry::AudioChannelRegistry], set with `#[audio_channel(name = "...")]`
+    const NAME: Option<&'static str> = None;
+
     fn play_event(id: AudioFiles) -> PlayEvent<Self>
     where
         Self: ACBounds;
     fn settings_event() -> SettingsEvent<Self>
     where
         Self: ACBounds;
+
+    /// Seeds the [ChannelSettings] when the channel is registered, set with `#[audio_channel(volume = .., delay_mode = .., settings = ..)]`
+    fn configure(_settings: &mut ChannelSettings<Self>)
+    where
+        Self: ACBounds,
+    {
+    }
+
+    /// Links the channel to its parent when it is registered, set with `#[audio_channel(parent = ..)]`
+    fn register_parent(_app: &mut App)
+    where
+        Self: ACBounds,
+    {
+    }
 }
@@ -1,17 +1,26 @@
+use std::time::Duration;
+
 use bevy::{
     app::{App, PostUpdate, Update},
-    audio::{AudioPlayer, AudioSink, AudioSinkPlayback, PlaybackMode, PlaybackSettings},
+    asset::{AssetServer, Assets, Handle, LoadState},
+    audio::{
+        AudioPlayer, AudioSink, AudioSinkPlayback, AudioSource, PlaybackMode, PlaybackSettings,
+    },
     ecs::{
+        component::Component,
         entity::Entity,
         event::{EventReader, EventWriter},
+        observer::Trigger,
         query::{Added, With},
         schedule::{
-            common_conditions::{on_event, resource_changed},
-            IntoSystemConfigs,
+            common_conditions::{on_event, resource_changed, resource_exists_and_changed},
+            Condition, IntoSystemConfigs,
         },
-        system::{Commands, Query, Res, ResMut},
+        system::{Commands, Query, Res, ResMut, SystemParam},
+        world::World,
     },
     hierarchy::BuildChildren,
+    log::{debug, warn},
     prelude::{DespawnRecursiveExt, RemovedComponents, Without},
     time::Time,
 };
@@ -19,32 +28,73 @@ use bevy::{
 use crate::{
     ac_assets::ACAssetLoader,
     ac_traits::CommandAudioTracks,
-    audio_files::AudioFiles,
+    audio_channel::AudioChannel,
+    audio_config::TRACK_CONFIGS,
+    audio_files::{AudioFiles, LoopPoints},
     bounds::ACBounds,
+    controller::PendingPlayback,
     delay_mode::DelayMode,
-    events::{PlayEvent, SettingsEvent},
+    dynamic::DynamicAudioRegistry,
+    dynamic_channel::{
+        forward_play_events, forward_settings_events, DynamicChannels, DynamicPlayEvent,
+        DynamicSettingsEvent,
+    },
+    events::{AudioError, AudioErrorKind, PlayAudio, PlayEvent, SettingsEvent},
     global::GlobalChannel,
     helpers,
+    intro_loop::{IntroLoopCache, IntroLoopSource},
+    loading::LoadStrategy,
+    offset::{get_loop_points, OffsetSource},
     plugin::HasChannel,
+    progress::PlaybackProgress,
+    registry::AudioChannelRegistry,
     resources::{AudioCache, ChannelSettings},
+    routing::AudioRouting,
+    synth::SynthSource,
 };
 
 pub trait ChannelRegistration {
-    fn register_audio_channel<Channel: ACBounds>(&mut self) -> &mut Self;
+    fn register_audio_channel<Channel: ACBounds + AudioChannel>(&mut self) -> &mut Self;
+    fn route_audio_folder<Channel: ACBounds>(&mut self, folder: &str) -> &mut Self;
+    fn set_audio_channel_parent<Channel: ACBounds, Parent: ACBounds>(&mut self) -> &mut Self;
 }
 
 impl ChannelRegistration for App {
     /// Registers an audio channel to the Bevy app
-    fn register_audio_channel<Channel: ACBounds>(&mut self) -> &mut Self {
+    fn register_audio_channel<Channel: ACBounds + AudioChannel>(&mut self) -> &mut Self {
         self.world_mut()
             .register_component_hooks::<Channel>()
             .on_add(|mut world, entity, _| {
                 world.commands().entity(entity).insert(HasChannel);
             });
 
+        let mut routing = self.world_mut().get_resource_or_init::<AudioRouting>();
+        for config in TRACK_CONFIGS {
+            if config.channel.is_some() && ChannelSettings::<Channel>::is_channel(config) {
+                routing.add_file::<Channel>(config.id);
+            }
+        }
+
+        self.world_mut()
+            .get_resource_or_init::<DynamicChannels>()
+            .register_typed::<Channel>();
+        let mut registry = self
+            .world_mut()
+            .get_resource_or_init::<AudioChannelRegistry>();
+        registry.register::<Channel>();
+        if let Some(name) = Channel::NAME {
+            registry.set_display_name::<Channel>(name);
+        }
+
+        if !self.world().contains_resource::<ChannelSettings<Channel>>() {
+            let mut settings = ChannelSettings::<Channel>::default();
+            Channel::configure(&mut settings);
+            self.insert_resource(settings);
+        }
+        Channel::register_parent(self);
+
         self.add_event::<PlayEvent<Channel>>()
             .add_event::<SettingsEvent<Channel>>()
-            .init_resource::<ChannelSettings<Channel>>()
             .init_resource::<AudioCache<Channel>>()
             .add_systems(
                 Update,
@@ -53,26 +103,88 @@ impl ChannelRegistration for App {
                     ecs_system::<Channel>,
                     // update_internal_timer_on_speed_change::<Channel>,
                     update_volume_on_insert::<Channel>,
-                    settings_event_reader::<Channel>.run_if(on_event::<SettingsEvent<Channel>>),
-                    update_track_volumes::<Channel>
-                        .run_if(resource_changed::<ChannelSettings<Channel>>),
+                    (
+                        forward_settings_events::<Channel>.run_if(on_event::<DynamicSettingsEvent>),
+                        settings_event_reader::<Channel>.run_if(on_event::<SettingsEvent<Channel>>),
+                    )
+                        .chain(),
+                    update_track_volumes::<Channel>.run_if(
+                        resource_changed::<ChannelSettings<Channel>>
+                            .or(resource_changed::<ChannelSettings<GlobalChannel>>),
+                    ),
                 ),
             )
             .add_systems(
                 PostUpdate,
                 (
                     remove_audio_components::<Channel>,
-                    play_event_reader::<Channel>.run_if(on_event::<PlayEvent<Channel>>),
+                    (
+                        forward_play_events::<Channel>.run_if(on_event::<DynamicPlayEvent>),
+                        play_event_reader::<Channel>.run_if(on_event::<PlayEvent<Channel>>),
+                    )
+                        .chain(),
                 ),
             );
 
+        self.add_observer(play_audio_observer::<Channel>);
+
+        // `#[audio_channel_marker]` can not name `ReflectComponent` in the scope of the channel, so it is registered here
         #[cfg(feature = "inspect")]
         self.register_type::<Channel>()
+            .register_type_data::<Channel, bevy::ecs::reflect::ReflectComponent>()
             .register_type::<ChannelSettings<Channel>>()
             .register_type::<AudioCache<Channel>>();
 
         self
     }
+
+    /// Puts audio files from a folder, relative to your assets folder, into a channel when they are spawned without one
+    ///
+    /// e.g. `app.route_audio_folder::<MusicChannel>("music")` makes `commands.spawn(AudioFiles::MusicThemeOGG)` play on the `MusicChannel`,
+    /// the channel has to be registered with `register_audio_channel`
+    fn route_audio_folder<Channel: ACBounds>(&mut self, folder: &str) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<AudioRouting>()
+            .add_folder::<Channel>(folder);
+        self
+    }
+
+    /// Makes a channel follow the volume and mute state of another channel, e.g. `FootstepsChannel` under `SfxChannel`
+    ///
+    /// Tracks of the channel play at its own volume times the volume of every parent and the [GlobalChannel].
+    /// This is what `#[audio_channel(parent = SfxChannel)]` calls when the channel is registered.
+    /// A parent that would make the channel its own ancestor is rejected with a warning
+    fn set_audio_channel_parent<Channel: ACBounds, Parent: ACBounds>(&mut self) -> &mut Self {
+        if !self
+            .world_mut()
+            .get_resource_or_init::<AudioChannelRegistry>()
+            .set_parent::<Channel, Parent>()
+        {
+            warn!(
+                "{} can not be the parent of {}, it would be its own parent",
+                std::any::type_name::<Parent>(),
+                std::any::type_name::<Channel>()
+            );
+            return self;
+        }
+        self.add_systems(
+            Update,
+            inherit_parent_volume::<Channel, Parent>
+                .run_if(resource_exists_and_changed::<ChannelSettings<Parent>>)
+                .after(settings_event_reader::<Parent>)
+                .before(update_track_volumes::<Channel>),
+        )
+    }
+}
+
+fn inherit_parent_volume<Channel: ACBounds, Parent: ACBounds>(
+    parent: Res<ChannelSettings<Parent>>,
+    mut channel: ResMut<ChannelSettings<Channel>>,
+) {
+    let volume = parent.get_effective_volume();
+    if channel.get_parent_volume() != volume {
+        channel.set_parent_volume(volume);
+    }
 }
 
 fn tick_audio_cache<Channel: ACBounds>(mut cache: ResMut<AudioCache<Channel>>, time: Res<Time>) {
@@ -82,24 +194,26 @@ fn tick_audio_cache<Channel: ACBounds>(mut cache: ResMut<AudioCache<Channel>>, t
 fn update_track_volumes<Channel: ACBounds>(
     channel: Res<ChannelSettings<Channel>>,
     global: Res<ChannelSettings<GlobalChannel>>,
-    track_query: Query<(&AudioSink, &AudioFiles), With<Channel>>,
+    track_query: Query<(&AudioSink, &AudioFiles, Option<&PlaybackSettings>), With<Channel>>,
 ) {
     let volume = helpers::get_normalized_volume(&channel, &global);
-    for (sink, id) in track_query.iter() {
-        let track_volume = channel.get_track_setting(id).volume.get();
-        sink.set_volume(volume * track_volume);
+    for (sink, id, playback) in track_query.iter() {
+        sink.set_volume(volume * helpers::get_track_volume(playback, &channel, id));
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn update_volume_on_insert<Channel: ACBounds>(
     channel: Res<ChannelSettings<Channel>>,
     global: Res<ChannelSettings<GlobalChannel>>,
-    sink_query: Query<&AudioSink, (Added<AudioSink>, With<Channel>)>,
+    sink_query: Query<(&AudioSink, Option<&AudioFiles>), (Added<AudioSink>, With<Channel>)>,
 ) {
     let volume = helpers::get_normalized_volume(&channel, &global);
-    for sink in sink_query.iter() {
-        let new_volume = sink.volume() * volume;
-        bevy::log::debug!("Setting volume from {} to {}", volume, new_volume);
+    for (sink, id) in sink_query.iter() {
+        // The sink starts at the volume of its PlaybackSettings
+        let gain = id.map_or(1.0, |id| channel.get_track_gain(id));
+        let new_volume = sink.volume() * gain * volume;
+        debug!("Setting volume from {} to {}", volume, new_volume);
         sink.set_volume(new_volume);
     }
 }
@@ -115,6 +229,7 @@ fn update_volume_on_insert<Channel: ACBounds>(
 //     }
 // }
 
+#[allow(clippy::type_complexity)]
 fn ecs_system<Channel: ACBounds>(
     query: Query<
         (Entity, &AudioFiles, Option<&PlaybackSettings>, &DelayMode),
@@ -126,9 +241,9 @@ fn ecs_system<Channel: ACBounds>(
     for (entity, audio_file, settings, mode) in query.iter() {
         let event = PlayEvent::<Channel>::new(*audio_file)
             .with_entity(entity)
-            .with_delay_mode(mode.clone());
+            .with_delay_mode(*mode);
         if let Some(settings) = settings {
-            events.push(event.with_settings(settings.clone()));
+            events.push(event.with_settings(*settings));
         } else {
             events.push(event);
         }
@@ -136,29 +251,198 @@ fn ecs_system<Channel: ACBounds>(
     ew.send_batch(events);
 }
 
-fn remove_audio_components<Channel: ACBounds>(
+#[allow(clippy::type_complexity)]
+pub(super) fn remove_audio_components<Channel: ACBounds>(
     mut commands: Commands,
     mut removed: RemovedComponents<AudioSink>,
-    channel_query: Query<&AudioFiles, With<Channel>>,
+    // Entities that still have an audio player are restarted, e.g. after seeking
+    channel_query: Query<
+        &AudioFiles,
+        (
+            With<Channel>,
+            Without<AudioPlayer<AudioSource>>,
+            Without<AudioPlayer<IntroLoopSource>>,
+            Without<AudioPlayer<OffsetSource>>,
+            Without<AudioPlayer<SynthSource>>,
+        ),
+    >,
 ) {
     for entity in removed.read() {
         if let Ok(track) = channel_query.get(entity) {
-            commands
-                .entity(entity)
-                .remove_audio_track(track)
-                .remove::<(Channel, AudioFiles)>();
+            commands.entity(entity).remove_audio_track(track).remove::<(
+                Channel,
+                AudioFiles,
+                PlaybackProgress,
+            )>();
         }
     }
 }
 
 fn play_event_reader<Channel: ACBounds>(
-    mut commands: Commands,
-    asset_loader: Res<ACAssetLoader>,
+    mut ctx: PlayContext,
     mut events: EventReader<PlayEvent<Channel>>,
     channel_settings: Res<ChannelSettings<Channel>>,
     mut audio_cache: ResMut<AudioCache<Channel>>,
 ) {
     for event in events.read() {
+        ctx.play(
+            event,
+            &channel_settings,
+            &mut audio_cache,
+            Channel::default(),
+            std::any::type_name::<Channel>(),
+        );
+    }
+}
+
+fn play_audio_observer<Channel: ACBounds>(
+    trigger: Trigger<PlayAudio<Channel>>,
+    mut ctx: PlayContext,
+    channel_settings: Res<ChannelSettings<Channel>>,
+    mut audio_cache: ResMut<AudioCache<Channel>>,
+) {
+    let mut event = trigger.event().event.retype::<Channel>();
+    if trigger.entity() == Entity::PLACEHOLDER {
+        event.child = false;
+    } else {
+        event.entity = Some(trigger.entity());
+    }
+    ctx.play(
+        &event,
+        &channel_settings,
+        &mut audio_cache,
+        Channel::default(),
+        std::any::type_name::<Channel>(),
+    );
+}
+
+/// What an [AudioFiles] id resolves to
+enum Track {
+    File(Handle<AudioSource>),
+    Synth(Handle<SynthSource>),
+}
+
+/// The audio player that is inserted for a track, with the loop points its progress wraps at
+enum Player {
+    File(Handle<AudioSource>),
+    IntroLoop(Handle<IntroLoopSource>, LoopPoints),
+    Offset(Handle<OffsetSource>, Option<LoopPoints>),
+    Synth(Handle<SynthSource>),
+}
+
+/// Read-only access to the resources that turn an [AudioFiles] id into something playable
+///
+/// Loads, intro loops and offset sources that have to be created are queued on [Commands],
+/// so the play systems of different channels can run in parallel
+#[derive(SystemParam)]
+pub(super) struct TrackSources<'w> {
+    asset_loader: Res<'w, ACAssetLoader>,
+    load_strategy: Res<'w, LoadStrategy>,
+    asset_server: Res<'w, AssetServer>,
+    audio_sources: Res<'w, Assets<AudioSource>>,
+    intro_loop_cache: Res<'w, IntroLoopCache>,
+    intro_loops: Res<'w, Assets<IntroLoopSource>>,
+    offset_sources: Res<'w, Assets<OffsetSource>>,
+    dynamic_registry: Res<'w, DynamicAudioRegistry>,
+}
+
+impl TrackSources<'_> {
+    fn resolve(&self, commands: &mut Commands, id: AudioFiles) -> Result<Track, AudioErrorKind> {
+        if id == AudioFiles::Unknown {
+            return Err(AudioErrorKind::UnknownFile);
+        }
+        if let Some(handle) = self.dynamic_registry.get_synth_handle(&id) {
+            return Ok(Track::Synth(handle));
+        }
+        let handle = if *self.load_strategy == LoadStrategy::Lazy {
+            self.asset_loader
+                .load_queued(commands, &self.asset_server, &id)
+        } else {
+            self.asset_loader.get(&id)
+        };
+        let handle = handle
+            .or_else(|| self.dynamic_registry.get_handle(&id))
+            .ok_or(AudioErrorKind::NotLoaded)?;
+        match self.asset_server.get_load_state(&handle) {
+            Some(LoadState::Failed(error)) => Err(error.as_ref().into()),
+            _ => Ok(Track::File(handle)),
+        }
+    }
+
+    /// Picks the source of an audio file, depending on the intro loop and start offset of the event
+    #[allow(clippy::too_many_arguments)]
+    fn file_player<Channel: ACBounds>(
+        &self,
+        commands: &mut Commands,
+        event: &PlayEvent<Channel>,
+        id: AudioFiles,
+        handler: Handle<AudioSource>,
+        offset: Option<Duration>,
+        channel_settings: &ChannelSettings<Channel>,
+        settings: &PlaybackSettings,
+    ) -> Player {
+        let intro_loop = event
+            .intro_loop
+            .unwrap_or_else(|| channel_settings.get_track_intro_loop(&id));
+        let loop_points = intro_loop.then(|| get_loop_points(&id)).flatten();
+        if let Some(offset) = offset {
+            if let Some(source) = self.audio_sources.get(&handler) {
+                let source = OffsetSource::new(source.clone(), loop_points, offset, settings.mode);
+                let handle = self.offset_sources.reserve_handle();
+                let asset = handle.clone();
+                commands.queue(move |world: &mut World| {
+                    world
+                        .resource_mut::<Assets<OffsetSource>>()
+                        .insert(&asset, source);
+                });
+                return Player::Offset(handle, loop_points);
+            }
+            warn!("{} has not been loaded yet, playing it from the start", id);
+        }
+        if let Some(loop_points) = loop_points {
+            if let Some(handle) = self.intro_loop_cache.get_or_queue(
+                commands,
+                id,
+                loop_points,
+                &handler,
+                &self.audio_sources,
+                &self.intro_loops,
+            ) {
+                return Player::IntroLoop(handle, loop_points);
+            }
+            warn!(
+                "{} has not been loaded yet, playing it without the intro loop",
+                id
+            );
+        }
+        Player::File(handler)
+    }
+}
+
+/// Everything needed to start playing a track, shared by typed and dynamic channels
+#[derive(SystemParam)]
+pub(super) struct PlayContext<'w, 's> {
+    commands: Commands<'w, 's>,
+    sources: TrackSources<'w>,
+    pending: Query<'w, 's, &'static PendingPlayback>,
+}
+
+impl PlayContext<'_, '_> {
+    /// Logs the error and sends it as an event once the commands are applied
+    pub(super) fn send_error(&mut self, error: AudioError) {
+        warn!("{}", error);
+        self.commands.send_event(error);
+    }
+
+    /// Plays the track of the event, `channel` is the component that is inserted along with it
+    pub(super) fn play<Channel: ACBounds, C: Component>(
+        &mut self,
+        event: &PlayEvent<Channel>,
+        channel_settings: &ChannelSettings<Channel>,
+        audio_cache: &mut AudioCache<Channel>,
+        channel: C,
+        channel_name: &str,
+    ) {
         let settings = if let Some(event_settings) = event.settings {
             event_settings
         } else {
@@ -169,78 +453,156 @@ fn play_event_reader<Channel: ACBounds>(
         } else {
             channel_settings.get_track_delay_mode(&event.id)
         };
-        let can_play = audio_cache.can_play(&event.id);
-        if delay_mode == DelayMode::Immediate || can_play {
-            if can_play {
-                let next_delay = delay_mode.get_delay(event.id.duration() / settings.speed);
-                audio_cache.set_entry(event.id, next_delay);
-            }
-            if let Some(handler) = asset_loader.get(&event.id) {
-                let bundle = (
-                    AudioPlayer::new(handler),
-                    settings,
-                    event.id,
-                    Channel::default(),
-                );
-                if let Some(dest_entity) = event.entity {
+        if event
+            .entity
+            .and_then(|entity| self.pending.get(entity).ok())
+            .is_some_and(|pending| pending.cancelled)
+        {
+            debug!("{} was stopped before it could play", event.id);
+            discard_entity::<Channel, C>(&mut self.commands, event, settings.mode);
+            return;
+        }
+        let duration = helpers::get_played_duration(
+            &event.id,
+            event.start_offset,
+            &self.sources.dynamic_registry,
+        );
+        if event.reserved || audio_cache.try_play(event.id, delay_mode, duration / settings.speed) {
+            let played = match self.sources.resolve(&mut self.commands, event.id) {
+                Ok(handler) => Some((event.id, handler)),
+                Err(kind) => {
+                    let fallback = channel_settings
+                        .get_fallback()
+                        .filter(|fallback| *fallback != event.id);
+                    self.send_error(AudioError {
+                        id: event.id,
+                        channel: Some(channel_name.to_string()),
+                        kind,
+                        fallback,
+                    });
+                    fallback.and_then(|fallback| {
+                        self.sources
+                            .resolve(&mut self.commands, fallback)
+                            .ok()
+                            .map(|handler| (fallback, handler))
+                    })
+                }
+            };
+            if let Some((id, track)) = played {
+                if let Track::File(handler) = &track {
+                    // Waiting keeps the event around, so the intro loop and offset can use the decoded file
+                    // and a file that fails to load still plays the fallback
+                    if let Some(LoadState::Loading) =
+                        self.sources.asset_server.get_load_state(handler)
+                    {
+                        debug!("{} is still loading, playing it once it has loaded", id);
+                        self.commands.send_event(event.deferred(id));
+                        return;
+                    }
+                }
+                let offset = event.start_offset.filter(|offset| !offset.is_zero());
+                let player = match track {
+                    Track::File(handler) => self.sources.file_player(
+                        &mut self.commands,
+                        event,
+                        id,
+                        handler,
+                        offset,
+                        channel_settings,
+                        &settings,
+                    ),
+                    Track::Synth(handle) => {
+                        if offset.is_some() {
+                            warn!("{} is a synth, playing it from the start", id);
+                        }
+                        Player::Synth(handle)
+                    }
+                };
+                let mut entity_commands = if let Some(dest_entity) = event.entity {
+                    if self.commands.get_entity(dest_entity).is_none() {
+                        debug!("{} was despawned before {} could play", dest_entity, id);
+                        return;
+                    }
                     if event.child {
-                        let child = commands.spawn(bundle).id();
-                        commands.entity(dest_entity).add_child(child);
+                        let child = self.commands.spawn_empty().id();
+                        self.commands.entity(dest_entity).add_child(child);
+                        self.commands.entity(child)
                     } else {
-                        commands.entity(dest_entity).insert(bundle);
+                        self.commands.entity(dest_entity)
                     }
                 } else {
-                    commands.spawn(bundle);
+                    self.commands.spawn_empty()
+                };
+                let mut progress = PlaybackProgress::new(helpers::get_track_duration(
+                    &id,
+                    &self.sources.dynamic_registry,
+                ));
+                let loop_points = match &player {
+                    Player::IntroLoop(_, loop_points) => Some(*loop_points),
+                    Player::Offset(_, loop_points) => *loop_points,
+                    _ => None,
+                };
+                progress.set_loop_points(&id, loop_points);
+                if let Some(offset) = offset.filter(|_| matches!(player, Player::Offset(..))) {
+                    progress.seek(
+                        offset.as_secs_f32(),
+                        matches!(settings.mode, PlaybackMode::Loop),
+                    );
                 }
-            }
-        } else if let Some(entity) = event.entity {
-            match settings.mode {
-                PlaybackMode::Despawn => {
-                    if event.child {
-                        return;
+                let bundle = (settings, id, channel, progress);
+                entity_commands.remove::<PendingPlayback>();
+                match player {
+                    Player::File(handler) => {
+                        entity_commands.insert((AudioPlayer::new(handler), bundle))
                     }
-                    commands.entity(entity).despawn_recursive();
-                }
-                PlaybackMode::Remove => {
-                    commands
-                        .entity(entity)
-                        .remove::<(Channel, PlaybackSettings, AudioFiles)>();
-                }
-                _ => {}
+                    Player::IntroLoop(handle, _) => {
+                        entity_commands.insert((AudioPlayer(handle), bundle))
+                    }
+                    Player::Offset(handle, _) => {
+                        entity_commands.insert((AudioPlayer(handle), bundle))
+                    }
+                    Player::Synth(handle) => entity_commands.insert((AudioPlayer(handle), bundle)),
+                };
+            } else {
+                discard_entity::<Channel, C>(&mut self.commands, event, settings.mode);
             }
+        } else {
+            discard_entity::<Channel, C>(&mut self.commands, event, settings.mode);
         }
     }
 }
 
+/// Cleans up the entity of an event whose track is not going to play, the same way it would have been once the track finished
+///
+/// Entities that were spawned for the event are always despawned
+pub(super) fn discard_entity<Channel: ACBounds, C: Component>(
+    commands: &mut Commands,
+    event: &PlayEvent<Channel>,
+    mode: PlaybackMode,
+) {
+    let Some(mut entity_commands) = event.entity.and_then(|entity| commands.get_entity(entity))
+    else {
+        return;
+    };
+    if event.spawned {
+        entity_commands.despawn_recursive();
+        return;
+    }
+    entity_commands.remove::<PendingPlayback>();
+    match mode {
+        PlaybackMode::Despawn if !event.child => entity_commands.despawn_recursive(),
+        PlaybackMode::Remove => {
+            entity_commands.remove::<(C, PlaybackSettings, AudioFiles)>();
+        }
+        _ => {}
+    }
+}
+
 fn settings_event_reader<Channel: ACBounds>(
     mut channel_settings: ResMut<ChannelSettings<Channel>>,
     mut events: EventReader<SettingsEvent<Channel>>,
 ) {
     for event in events.read() {
-        if let Some(volume) = event.volume {
-            channel_settings.set_channel_volume(volume);
-        }
-        if let Some(id) = event.track {
-            if let Some(delay_mode) = event.delay_mode {
-                channel_settings.set_track_delay_mode(id, delay_mode);
-            }
-            if let Some(settings) = event.settings {
-                channel_settings.set_track_settings(id, settings);
-            }
-        } else if event.all {
-            if let Some(delay_mode) = event.delay_mode {
-                channel_settings.set_all_track_delay_modes(delay_mode);
-            }
-            if let Some(settings) = event.settings {
-                channel_settings.set_all_track_settings(settings);
-            }
-        } else {
-            if let Some(delay_mode) = event.delay_mode {
-                channel_settings.set_default_delay_mode(delay_mode);
-            }
-            if let Some(settings) = event.settings {
-                channel_settings.set_default_settings(settings);
-            }
-        }
+        channel_settings.apply_event(event);
     }
 }
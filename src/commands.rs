@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::{
+        entity::Entity,
+        event::Events,
+        system::{Commands, EntityCommands},
+        world::World,
+    },
+    log::warn,
+};
+
+use crate::{
+    bounds::ACBounds,
+    events::{AudioError, AudioErrorKind, PlayEvent},
+    offset,
+};
+
+/// Plays audio where an [EventWriter](bevy::ecs::event::EventWriter) is not available, e.g. in exclusive systems, observers, or custom commands
+///
+/// The [PlayEvent] is sent to the channel as usual, so the channel settings and [DelayMode](crate::delay_mode::DelayMode) apply the same way
+///
+/// # Example
+/// ```ignore
+/// use bevy::prelude::*;
+/// use bevy_audio_controller::prelude::*;
+///
+/// fn on_hit(trigger: Trigger<Hit>, mut commands: Commands) {
+///     commands.play_audio::<SfxChannel>(AudioFiles::HitOGG);
+///     commands
+///         .entity(trigger.entity())
+///         .play_audio::<SfxChannel>(PlayEvent::new(AudioFiles::FireOGG).with_settings(PlaybackSettings::REMOVE));
+/// }
+///
+/// fn exclusive(world: &mut World) {
+///     world.play_audio::<SfxChannel>(AudioFiles::FireOGG);
+/// }
+/// ```
+pub trait PlayAudioExt {
+    /// Plays an audio file or a [PlayEvent] on the channel
+    fn play_audio<Channel: ACBounds>(&mut self, event: impl Into<PlayEvent<Channel>>) -> &mut Self;
+}
+
+impl PlayAudioExt for Commands<'_, '_> {
+    fn play_audio<Channel: ACBounds>(&mut self, event: impl Into<PlayEvent<Channel>>) -> &mut Self {
+        let event = event.into();
+        self.queue(move |world: &mut World| {
+            world.play_audio(event);
+        });
+        self
+    }
+}
+
+impl PlayAudioExt for EntityCommands<'_> {
+    /// Plays the audio on this entity, like [PlayEvent::with_entity]
+    fn play_audio<Channel: ACBounds>(&mut self, event: impl Into<PlayEvent<Channel>>) -> &mut Self {
+        let event = event.into();
+        self.queue(move |entity: Entity, world: &mut World| {
+            world.play_audio(event.with_entity(entity));
+        });
+        self
+    }
+}
+
+impl PlayAudioExt for World {
+    fn play_audio<Channel: ACBounds>(&mut self, event: impl Into<PlayEvent<Channel>>) -> &mut Self {
+        let event = event.into();
+        if self.contains_resource::<Events<PlayEvent<Channel>>>() {
+            self.send_event(event);
+        } else {
+            let error = AudioError {
+                id: event.id,
+                channel: Some(std::any::type_name::<Channel>().to_string()),
+                kind: AudioErrorKind::UnregisteredChannel,
+                fallback: None,
+            };
+            warn!("{}", error);
+            self.send_event(error);
+        }
+        self
+    }
+}
+
+/// Moves the track that is playing on an entity to another position
+///
+/// The track is restarted from `position` in the same frame, its [PlaybackSettings](bevy::audio::PlaybackSettings) and
+/// [PlaybackProgress](crate::progress::PlaybackProgress) are kept
+///
+/// # Example
+/// ```ignore
+/// fn skip_intro(mut commands: Commands, music: Single<Entity, With<MusicChannel>>) {
+///     commands.entity(*music).seek_audio(Duration::from_secs(12));
+/// }
+/// ```
+pub trait SeekAudioExt {
+    fn seek_audio(&mut self, position: Duration) -> &mut Self;
+}
+
+impl SeekAudioExt for EntityCommands<'_> {
+    fn seek_audio(&mut self, position: Duration) -> &mut Self {
+        self.queue(move |entity: Entity, world: &mut World| {
+            offset::seek(entity, world, position);
+        });
+        self
+    }
+}
@@ -0,0 +1,357 @@
+use std::time::Duration;
+
+use bevy::{
+    audio::{
+        AudioPlayer, AudioSink, AudioSinkPlayback, AudioSource, PlaybackMode, PlaybackSettings,
+        Volume,
+    },
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::EventWriter,
+        query::With,
+        system::{Commands, Query, Res, ResMut, SystemParam},
+        world::World,
+    },
+    hierarchy::{BuildChildren, DespawnRecursiveExt},
+};
+
+use crate::{
+    audio_files::AudioFiles,
+    bounds::ACBounds,
+    channel::discard_entity,
+    commands::SeekAudioExt,
+    delay_mode::DelayMode,
+    dynamic::DynamicAudioRegistry,
+    events::{PlayEvent, SettingsEvent},
+    global::GlobalChannel,
+    helpers,
+    intro_loop::IntroLoopSource,
+    offset::OffsetSource,
+    resources::{AudioCache, ChannelSettings},
+    synth::SynthSource,
+};
+
+/// The result of playing audio with an [AudioController]
+///
+/// The entity carries its generation, so a handle to a track that has finished and been despawned
+/// never addresses a different entity that reused the index
+///
+/// A handle is returned before the audio file is resolved, so a [PlaybackHandle::Playing] track can still
+/// be waiting for its file to load, or never start at all when the file is unknown or fails to load
+/// and the channel has no fallback. The entity is cleaned up in that case, like a track that finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlaybackHandle {
+    /// The track plays on this entity once its audio file is ready, the audio components are inserted at the earliest at the end of the frame
+    Playing(Entity),
+    /// The [DelayMode] of the track did not allow it to play yet
+    Rejected,
+}
+
+impl PlaybackHandle {
+    /// Returns the entity the track plays on, `None` if it was rejected
+    pub fn entity(&self) -> Option<Entity> {
+        match self {
+            PlaybackHandle::Playing(entity) => Some(*entity),
+            PlaybackHandle::Rejected => None,
+        }
+    }
+
+    /// Returns true if the [DelayMode] of the track did not allow it to play
+    pub fn is_rejected(&self) -> bool {
+        matches!(self, PlaybackHandle::Rejected)
+    }
+}
+
+/// Marks an entity that an [AudioController] sent a [PlayEvent] for, until its audio components are inserted
+///
+/// Lets [AudioController::stop_playback] cancel a track that has not started yet
+#[derive(Component, Debug, Default)]
+pub(super) struct PendingPlayback {
+    pub(super) cancelled: bool,
+}
+
+/// A [SystemParam] that bundles the events and resources of a channel for the common tasks
+///
+/// Everything goes through the same [PlayEvent] and [SettingsEvent] events as before,
+/// so the events can still be read in tests to check what a system did
+///
+/// # Example
+/// ```ignore
+/// use bevy::prelude::*;
+/// use bevy_audio_controller::prelude::*;
+///
+/// #[audio_channel_marker]
+/// struct SfxChannel;
+///
+/// fn play_fire(mut sfx: AudioController<SfxChannel>, input: Res<ButtonInput<KeyCode>>) {
+///     if input.just_pressed(KeyCode::Space) {
+///         sfx.play(AudioFiles::FireOGG);
+///     }
+///     if input.just_pressed(KeyCode::KeyR) {
+///         // keep the handle around to address this instance later
+///         let handle = sfx.play(AudioFiles::ReloadOGG);
+///         sfx.set_playback_speed(handle, 1.5);
+///     }
+///     if input.just_pressed(KeyCode::KeyM) {
+///         sfx.set_volume(0.0);
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct AudioController<'w, 's, Channel: ACBounds> {
+    commands: Commands<'w, 's>,
+    play_ew: EventWriter<'w, PlayEvent<Channel>>,
+    settings_ew: EventWriter<'w, SettingsEvent<Channel>>,
+    settings: Res<'w, ChannelSettings<Channel>>,
+    global: Res<'w, ChannelSettings<GlobalChannel>>,
+    cache: ResMut<'w, AudioCache<Channel>>,
+    dynamic_registry: Res<'w, DynamicAudioRegistry>,
+    tracks: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static AudioFiles,
+            &'static AudioSink,
+            Option<&'static PlaybackSettings>,
+        ),
+        With<Channel>,
+    >,
+}
+
+impl<Channel: ACBounds> AudioController<'_, '_, Channel> {
+    /// Plays an audio file on the channel with the channel settings
+    pub fn play(&mut self, id: AudioFiles) -> PlaybackHandle {
+        self.send(PlayEvent::new(id))
+    }
+
+    /// Plays an audio file on an existing entity
+    pub fn play_on(&mut self, entity: Entity, id: AudioFiles) -> PlaybackHandle {
+        self.send(PlayEvent::new(id).with_entity(entity))
+    }
+
+    /// Sends a [PlayEvent] for anything the shortcuts don't cover
+    ///
+    /// The [DelayMode] is checked right away, so the handle tells whether the track is going to play
+    pub fn send(&mut self, mut event: PlayEvent<Channel>) -> PlaybackHandle {
+        let settings = event
+            .settings
+            .unwrap_or_else(|| self.settings.get_track_setting(&event.id));
+        let delay_mode = event
+            .delay_mode
+            .unwrap_or_else(|| self.settings.get_track_delay_mode(&event.id));
+        let duration =
+            helpers::get_played_duration(&event.id, event.start_offset, &self.dynamic_registry);
+        if !self
+            .cache
+            .try_play(event.id, delay_mode, duration / settings.speed)
+        {
+            discard_entity::<Channel, Channel>(&mut self.commands, &event, settings.mode);
+            return PlaybackHandle::Rejected;
+        }
+        let entity = match event.entity {
+            Some(parent) if event.child => {
+                let child = self.commands.spawn_empty().id();
+                self.commands.entity(parent).add_child(child);
+                child
+            }
+            Some(entity) => entity,
+            None => self.commands.spawn_empty().id(),
+        };
+        event.spawned = event.entity.is_none() || event.child;
+        if let Some(mut entity_commands) = self.commands.get_entity(entity) {
+            entity_commands.try_insert(PendingPlayback::default());
+        }
+        event.entity = Some(entity);
+        event.child = false;
+        event.reserved = true;
+        self.play_ew.send(event);
+        PlaybackHandle::Playing(entity)
+    }
+
+    /// Stops every track of an audio file that is playing on the channel
+    ///
+    /// Entities that were played with [PlaybackSettings::DESPAWN] are despawned, the audio components are removed from all others
+    pub fn stop(&mut self, id: AudioFiles) {
+        self.stop_where(|track| *track == id);
+    }
+
+    /// Stops every track that is playing on the channel
+    pub fn stop_all(&mut self) {
+        self.stop_where(|_| true);
+    }
+
+    /// Stops the track of a [PlaybackHandle], does nothing if it already finished
+    ///
+    /// A track that has not started yet is cancelled, so it never plays
+    pub fn stop_playback(&mut self, handle: PlaybackHandle) {
+        let Some(entity) = handle.entity() else {
+            return;
+        };
+        if let Ok((entity, _, sink, settings)) = self.tracks.get(entity) {
+            stop_track(&mut self.commands, entity, sink, settings);
+        } else if let Some(mut entity_commands) = self.commands.get_entity(entity) {
+            entity_commands.queue(cancel_playback);
+        }
+    }
+
+    /// Returns true if the track of a [PlaybackHandle] is playing and not paused
+    ///
+    /// Stays false while the track is waiting to start, and for a track that never starts
+    pub fn is_active(&self, handle: PlaybackHandle) -> bool {
+        handle
+            .entity()
+            .and_then(|entity| self.tracks.get(entity).ok())
+            .is_some_and(|(_, _, sink, _)| !sink.is_paused() && !sink.empty())
+    }
+
+    /// Sets the volume of the track of a [PlaybackHandle] relative to the channel, scale is 0.0 - 1.0
+    ///
+    /// The volume is kept when the channel volume changes, call it every frame to fade a track
+    pub fn set_playback_volume(&mut self, handle: PlaybackHandle, volume: f32) {
+        let Some(entity) = handle.entity() else {
+            return;
+        };
+        let Ok((entity, id, sink, _)) = self.tracks.get(entity) else {
+            return;
+        };
+        let gain = self.settings.get_track_gain(id);
+        sink.set_volume(
+            volume * gain * helpers::get_normalized_volume(&self.settings, &self.global),
+        );
+        self.commands
+            .entity(entity)
+            .queue(move |entity: Entity, world: &mut World| {
+                if let Some(mut settings) = world.get_mut::<PlaybackSettings>(entity) {
+                    settings.volume = Volume::new(volume);
+                }
+            });
+    }
+
+    /// Moves the track of a [PlaybackHandle] to `position`, see [SeekAudioExt]
+    pub fn seek_playback(&mut self, handle: PlaybackHandle, position: Duration) {
+        let Some(entity) = handle.entity() else {
+            return;
+        };
+        if self.tracks.contains(entity) {
+            self.commands.entity(entity).seek_audio(position);
+        }
+    }
+
+    /// Sets the speed of the track of a [PlaybackHandle], this also changes the pitch
+    pub fn set_playback_speed(&mut self, handle: PlaybackHandle, speed: f32) {
+        let Some(entity) = handle.entity() else {
+            return;
+        };
+        let Ok((entity, _, sink, _)) = self.tracks.get(entity) else {
+            return;
+        };
+        sink.set_speed(speed);
+        self.commands
+            .entity(entity)
+            .queue(move |entity: Entity, world: &mut World| {
+                if let Some(mut settings) = world.get_mut::<PlaybackSettings>(entity) {
+                    settings.speed = speed;
+                }
+            });
+    }
+
+    fn stop_where(&mut self, filter: impl Fn(&AudioFiles) -> bool) {
+        for (entity, track, sink, settings) in self.tracks.iter() {
+            if filter(track) {
+                stop_track(&mut self.commands, entity, sink, settings);
+            }
+        }
+    }
+
+    /// Returns true if a track of the audio file is playing on the channel and not paused
+    pub fn is_playing(&self, id: AudioFiles) -> bool {
+        self.tracks
+            .iter()
+            .any(|(_, track, sink, _)| *track == id && !sink.is_paused() && !sink.empty())
+    }
+
+    /// Returns true if playing the audio file right now would not be held back by its [DelayMode]
+    pub fn can_play(&self, id: AudioFiles) -> bool {
+        self.settings.get_track_delay_mode(&id) == DelayMode::Immediate || self.cache.can_play(&id)
+    }
+
+    /// Returns the volume of the channel
+    pub fn volume(&self) -> f32 {
+        self.settings.get_channel_volume()
+    }
+
+    /// Sets the volume of the channel, scale is 0.0 - 1.0
+    pub fn set_volume(&mut self, volume: f32) {
+        self.settings_ew
+            .send(SettingsEvent::new().with_volume(volume));
+    }
+
+    /// Mutes or unmutes the channel
+    pub fn set_muted(&mut self, muted: bool) {
+        self.settings_ew
+            .send(SettingsEvent::new().with_muted(muted));
+    }
+
+    /// Sends a [SettingsEvent] for anything the shortcuts don't cover
+    pub fn send_settings(&mut self, event: SettingsEvent<Channel>) {
+        self.settings_ew.send(event);
+    }
+
+    /// Returns the [ChannelSettings] of the channel
+    pub fn settings(&self) -> &ChannelSettings<Channel> {
+        &self.settings
+    }
+}
+
+/// Cancels a track that has no [AudioSink] yet, either before or after its audio components are inserted
+fn cancel_playback(entity: Entity, world: &mut World) {
+    let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    if let Some(mut pending) = entity_mut.get_mut::<PendingPlayback>() {
+        pending.cancelled = true;
+        return;
+    }
+    let has_player = entity_mut.contains::<AudioPlayer<AudioSource>>()
+        || entity_mut.contains::<AudioPlayer<IntroLoopSource>>()
+        || entity_mut.contains::<AudioPlayer<OffsetSource>>()
+        || entity_mut.contains::<AudioPlayer<SynthSource>>();
+    if !has_player || entity_mut.contains::<AudioSink>() {
+        return;
+    }
+    if entity_mut
+        .get::<PlaybackSettings>()
+        .is_some_and(|settings| matches!(settings.mode, PlaybackMode::Despawn))
+    {
+        entity_mut.despawn_recursive();
+    } else {
+        entity_mut.remove::<(
+            AudioPlayer<AudioSource>,
+            AudioPlayer<IntroLoopSource>,
+            AudioPlayer<OffsetSource>,
+            AudioPlayer<SynthSource>,
+        )>();
+    }
+}
+
+fn stop_track(
+    commands: &mut Commands,
+    entity: Entity,
+    sink: &AudioSink,
+    settings: Option<&PlaybackSettings>,
+) {
+    sink.stop();
+    if settings.is_some_and(|settings| matches!(settings.mode, PlaybackMode::Despawn)) {
+        commands.entity(entity).despawn_recursive();
+    } else {
+        commands.entity(entity).remove::<(
+            AudioPlayer<AudioSource>,
+            AudioPlayer<IntroLoopSource>,
+            AudioPlayer<OffsetSource>,
+            AudioPlayer<SynthSource>,
+            AudioSink,
+        )>();
+    }
+}
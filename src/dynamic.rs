@@ -0,0 +1,475 @@
+use std::{fmt, str::FromStr};
+
+use bevy::{
+    asset::{AssetServer, Assets, Handle, LoadedFolder},
+    audio::{AudioSource, Decodable, Source},
+    ecs::system::{Res, ResMut, Resource},
+    log::{debug, warn},
+    tasks::{block_on, poll_once, AsyncComputeTaskPool, Task},
+    utils::hashbrown::HashMap,
+};
+#[cfg(feature = "inspect")]
+use bevy::{ecs::reflect::ReflectResource, reflect::Reflect};
+
+use crate::{
+    audio_files::{AudioFiles, UnknownAudioFileError},
+    helpers,
+    synth::{Synth, SynthSource},
+};
+
+/// The path prefix of synths, so they never share an id with a file
+const SYNTH_SCHEME: &str = "synth://";
+
+/// The id of an audio file that was registered at runtime, e.g. from a mod or downloaded content
+///
+/// It is the same 64-bit FNV-1a hash of the asset path that [AudioFiles::id] uses,
+/// so it is stable between runs as long as the path does not change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+pub struct DynamicAudio(pub(crate) u64);
+
+impl DynamicAudio {
+    /// Creates the id for an asset path, this does not register or load the file
+    pub fn from_path(path: &str) -> Self {
+        Self(helpers::fnv1a(&path.replace('\\', "/")))
+    }
+
+    /// Returns the numeric id
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for DynamicAudio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "dynamic:{:016x}", self.0)
+    }
+}
+
+/// Parses the `dynamic:{:016x}` form [fmt::Display] writes, so ids survive a round trip through strings and serde
+impl FromStr for DynamicAudio {
+    type Err = UnknownAudioFileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix("dynamic:")
+            .filter(|hex| hex.len() == 16 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+            .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+            .map(Self)
+            .ok_or_else(|| UnknownAudioFileError(s.to_string()))
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+struct DynamicAudioEntry {
+    path: String,
+    handle: Handle<AudioSource>,
+    /// Measured from the [AudioSource] once it has loaded, see [update_dynamic_durations]
+    duration: Option<f32>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+struct SynthEntry {
+    path: String,
+    synth: Synth,
+    handle: Handle<SynthSource>,
+}
+
+/// Keeps track of audio files that are not part of your assets folder at build time
+///
+/// Registered files get an [AudioFiles::Dynamic] id that works everywhere a generated [AudioFiles] variant does,
+/// such as [crate::events::PlayEvent], the per track maps of [crate::resources::ChannelSettings], and cooldowns
+///
+/// # Example
+/// ```ignore
+/// use bevy::prelude::*;
+/// use bevy_audio_controller::prelude::*;
+///
+/// fn load_mods(mut registry: ResMut<DynamicAudioRegistry>, asset_server: Res<AssetServer>) {
+///     registry.load_folder(&asset_server, "mods/sounds");
+///     let id = registry.register(&asset_server, "mods/horn.ogg");
+/// }
+/// ```
+///
+/// Generated sounds are registered the same way, see [Synth]
+/// ```ignore
+/// fn register_synths(mut registry: ResMut<DynamicAudioRegistry>, mut synths: ResMut<Assets<SynthSource>>) {
+///     let laser = registry.register_synth(&mut synths, "laser", Synth::preset(SynthPreset::Laser));
+/// }
+/// ```
+#[derive(Default, Resource)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+#[cfg_attr(feature = "inspect", reflect(Resource))]
+pub struct DynamicAudioRegistry {
+    entries: HashMap<DynamicAudio, DynamicAudioEntry>,
+    synths: HashMap<DynamicAudio, SynthEntry>,
+    pending_folders: Vec<Handle<LoadedFolder>>,
+    /// Files whose length is counted on the [AsyncComputeTaskPool]
+    #[cfg_attr(feature = "inspect", reflect(ignore))]
+    duration_tasks: HashMap<DynamicAudio, Task<f32>>,
+}
+
+impl DynamicAudioRegistry {
+    /// Loads an audio file by its asset path and returns its id
+    ///
+    /// Paths that are part of your assets folder at build time return the generated variant instead,
+    /// paths whose id is already taken by another file return [AudioFiles::Unknown]
+    pub fn register(&mut self, asset_server: &AssetServer, path: &str) -> AudioFiles {
+        if let Some(id) = Self::generated(path) {
+            return id;
+        }
+        let dynamic = DynamicAudio::from_path(path);
+        if !self.is_free(dynamic, path) {
+            return AudioFiles::Unknown;
+        }
+        self.entries
+            .entry(dynamic)
+            .or_insert_with(|| DynamicAudioEntry {
+                path: path.to_string(),
+                handle: asset_server.load(path.to_string()),
+                duration: None,
+            });
+        AudioFiles::Dynamic(dynamic)
+    }
+
+    /// Registers an [AudioSource] handle that was loaded elsewhere and returns its id
+    ///
+    /// Returns [AudioFiles::Unknown] if the handle does not have an asset path
+    pub fn register_handle(&mut self, handle: Handle<AudioSource>) -> AudioFiles {
+        let Some(path) = handle.path().map(|path| path.to_string()) else {
+            warn!("Can not register an audio handle without an asset path");
+            return AudioFiles::Unknown;
+        };
+        if let Some(id) = Self::generated(&path) {
+            return id;
+        }
+        let dynamic = DynamicAudio::from_path(&path);
+        if !self.is_free(dynamic, &path) {
+            return AudioFiles::Unknown;
+        }
+        debug!("Registering dynamic audio file: {}", path);
+        self.entries
+            .entry(dynamic)
+            .or_insert_with(|| DynamicAudioEntry {
+                path,
+                handle,
+                duration: None,
+            });
+        AudioFiles::Dynamic(dynamic)
+    }
+
+    /// Registers a generated sound under a name and returns its id
+    ///
+    /// The id is the same for the same name, registering the name again replaces the sound.
+    /// Returns [AudioFiles::Unknown] if the id is already taken by another file
+    pub fn register_synth(
+        &mut self,
+        synths: &mut Assets<SynthSource>,
+        name: &str,
+        synth: Synth,
+    ) -> AudioFiles {
+        let path = format!("{SYNTH_SCHEME}{name}");
+        let dynamic = DynamicAudio::from_path(&path);
+        if !self.is_free(dynamic, &path) {
+            return AudioFiles::Unknown;
+        }
+        debug!("Registering synth: {}", name);
+        let handle = synths.add(SynthSource::new(synth));
+        self.synths.insert(
+            dynamic,
+            SynthEntry {
+                path,
+                synth,
+                handle,
+            },
+        );
+        AudioFiles::Dynamic(dynamic)
+    }
+
+    /// Returns the parameters of a registered synth
+    pub fn get_synth(&self, id: &AudioFiles) -> Option<&Synth> {
+        self.get_synth_entry(id).map(|entry| &entry.synth)
+    }
+
+    /// Loads every file in a folder with [AssetServer::load_folder],
+    /// the audio files are registered once the whole folder has loaded
+    pub fn load_folder(&mut self, asset_server: &AssetServer, path: &str) {
+        self.pending_folders
+            .push(asset_server.load_folder(path.to_string()));
+    }
+
+    /// Removes a file or synth from the registry, returns false if it was not registered
+    pub fn unregister(&mut self, id: &AudioFiles) -> bool {
+        match id {
+            AudioFiles::Dynamic(dynamic) => {
+                self.duration_tasks.remove(dynamic);
+                self.entries.remove(dynamic).is_some() || self.synths.remove(dynamic).is_some()
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the registered file for an asset path, including generated ones
+    pub fn from_path(&self, path: &str) -> Option<AudioFiles> {
+        Self::generated(path).or_else(|| {
+            let dynamic = DynamicAudio::from_path(path);
+            self.contains_dynamic(&dynamic)
+                .then_some(AudioFiles::Dynamic(dynamic))
+        })
+    }
+
+    /// Returns the file for an id from [AudioFiles::id], including generated ones
+    pub fn from_id(&self, id: u64) -> Option<AudioFiles> {
+        AudioFiles::from_id(id).or_else(|| {
+            self.contains_dynamic(&DynamicAudio(id))
+                .then_some(AudioFiles::Dynamic(DynamicAudio(id)))
+        })
+    }
+
+    /// Returns the asset path of a registered file, synths return `synth://` followed by their name
+    pub fn get_path(&self, id: &AudioFiles) -> Option<&str> {
+        self.get_entry(id)
+            .map(|entry| entry.path.as_str())
+            .or_else(|| self.get_synth_entry(id).map(|entry| entry.path.as_str()))
+    }
+
+    /// Returns the duration of a registered file or synth in seconds, `None` until the file has loaded
+    pub fn get_duration(&self, id: &AudioFiles) -> Option<f32> {
+        self.get_entry(id)
+            .and_then(|entry| entry.duration)
+            .or_else(|| self.get_synth(id).map(Synth::duration))
+    }
+
+    /// Returns whether the file or synth is registered
+    pub fn contains(&self, id: &AudioFiles) -> bool {
+        self.get_entry(id).is_some() || self.get_synth_entry(id).is_some()
+    }
+
+    /// Iterates over all registered files and synths
+    pub fn iter(&self) -> impl Iterator<Item = AudioFiles> + '_ {
+        self.entries
+            .keys()
+            .chain(self.synths.keys())
+            .map(|dynamic| AudioFiles::Dynamic(*dynamic))
+    }
+
+    /// Returns true while the length of a file is still being counted
+    pub(super) fn is_measuring(&self) -> bool {
+        !self.duration_tasks.is_empty()
+    }
+
+    pub(super) fn get_handle(&self, id: &AudioFiles) -> Option<Handle<AudioSource>> {
+        self.get_entry(id).map(|entry| entry.handle.clone())
+    }
+
+    pub(super) fn get_synth_handle(&self, id: &AudioFiles) -> Option<Handle<SynthSource>> {
+        self.get_synth_entry(id).map(|entry| entry.handle.clone())
+    }
+
+    /// Ids are hashes, so a different path can end up with the id of a generated or registered file,
+    /// which would make the two indistinguishable
+    fn is_free(&self, dynamic: DynamicAudio, path: &str) -> bool {
+        let taken_by = AudioFiles::from_id(dynamic.0)
+            .map(|file| file.to_string())
+            .or_else(|| {
+                self.get_path(&AudioFiles::Dynamic(dynamic))
+                    .filter(|other| *other != path)
+                    .map(str::to_string)
+            });
+        if let Some(other) = taken_by {
+            warn!(
+                "{} has the same id as {}, rename it to register it",
+                path, other
+            );
+            return false;
+        }
+        true
+    }
+
+    fn contains_dynamic(&self, dynamic: &DynamicAudio) -> bool {
+        self.entries.contains_key(dynamic) || self.synths.contains_key(dynamic)
+    }
+
+    fn get_synth_entry(&self, id: &AudioFiles) -> Option<&SynthEntry> {
+        match id {
+            AudioFiles::Dynamic(dynamic) => self.synths.get(dynamic),
+            _ => None,
+        }
+    }
+
+    fn get_entry(&self, id: &AudioFiles) -> Option<&DynamicAudioEntry> {
+        match id {
+            AudioFiles::Dynamic(dynamic) => self.entries.get(dynamic),
+            _ => None,
+        }
+    }
+
+    fn generated(path: &str) -> Option<AudioFiles> {
+        path.parse::<AudioFiles>()
+            .ok()
+            .filter(|id| *id != AudioFiles::Unknown)
+    }
+}
+
+pub(super) fn register_loaded_folders(
+    mut registry: ResMut<DynamicAudioRegistry>,
+    folders: Res<Assets<LoadedFolder>>,
+) {
+    if registry.pending_folders.is_empty() {
+        return;
+    }
+    let pending = std::mem::take(&mut registry.pending_folders);
+    for handle in pending {
+        if let Some(folder) = folders.get(&handle) {
+            for file in folder.handles.iter() {
+                if let Ok(audio) = file.clone().try_typed::<AudioSource>() {
+                    registry.register_handle(audio);
+                }
+            }
+        } else {
+            registry.pending_folders.push(handle);
+        }
+    }
+}
+
+/// Measures the length of files once they have loaded, counting the samples happens on the [AsyncComputeTaskPool]
+pub(super) fn update_dynamic_durations(
+    mut registry: ResMut<DynamicAudioRegistry>,
+    sources: Res<Assets<AudioSource>>,
+) {
+    let registry = &mut *registry;
+    registry.duration_tasks.retain(|dynamic, task| {
+        let Some(duration) = block_on(poll_once(task)) else {
+            return true;
+        };
+        if let Some(entry) = registry.entries.get_mut(dynamic) {
+            debug!("Dynamic audio file {} is {}s long", entry.path, duration);
+            entry.duration = Some(duration);
+        }
+        false
+    });
+
+    for (dynamic, entry) in registry.entries.iter_mut() {
+        if entry.duration.is_some() || registry.duration_tasks.contains_key(dynamic) {
+            continue;
+        }
+        let Some(source) = sources.get(&entry.handle) else {
+            continue;
+        };
+        if let Some(duration) = get_known_duration(source) {
+            debug!("Dynamic audio file {} is {}s long", entry.path, duration);
+            entry.duration = Some(duration);
+        } else {
+            let source = source.clone();
+            let task = AsyncComputeTaskPool::get().spawn(async move { count_duration(&source) });
+            registry.duration_tasks.insert(*dynamic, task);
+        }
+    }
+}
+
+/// The length of a source if its decoder knows it up front
+fn get_known_duration(source: &AudioSource) -> Option<f32> {
+    source
+        .decoder()
+        .total_duration()
+        .map(|duration| duration.as_secs_f32())
+}
+
+/// Some decoders do not know their length up front, in which case the samples are counted,
+/// which decodes the whole file so it should not run on the main thread
+pub(super) fn count_duration(source: &AudioSource) -> f32 {
+    let decoder = source.decoder();
+    if let Some(duration) = decoder.total_duration() {
+        return duration.as_secs_f32();
+    }
+    let channels = decoder.channels().max(1) as f32;
+    let sample_rate = decoder.sample_rate().max(1) as f32;
+    decoder.count() as f32 / channels / sample_rate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_files::ALL_FILES;
+
+    #[test]
+    fn dynamic_ids_round_trip() {
+        for id in [0, 1, 0xdead_beef, u64::MAX] {
+            let dynamic = DynamicAudio(id);
+            assert_eq!(
+                dynamic.to_string().parse::<DynamicAudio>().unwrap(),
+                dynamic
+            );
+
+            let file = AudioFiles::Dynamic(dynamic);
+            assert_eq!(file.to_string().parse::<AudioFiles>().unwrap(), file);
+        }
+        let from_path = AudioFiles::Dynamic(DynamicAudio::from_path("mods/laser.ogg"));
+        assert_eq!(
+            from_path.to_string().parse::<AudioFiles>().unwrap(),
+            from_path
+        );
+    }
+
+    #[test]
+    fn static_ids_round_trip() {
+        for file in ALL_FILES.into_iter().chain([AudioFiles::Unknown]) {
+            assert_eq!(file.to_string().parse::<AudioFiles>().unwrap(), file);
+        }
+    }
+
+    #[test]
+    fn malformed_dynamic_ids_are_rejected() {
+        for invalid in [
+            "dynamic:",
+            "dynamic:1234",
+            "dynamic:+00000000000000f",
+            "dynamic:000000000000000g",
+            "dynamic:00000000000000001",
+            "00000000deadbeef",
+        ] {
+            assert!(invalid.parse::<DynamicAudio>().is_err(), "{}", invalid);
+            assert!(invalid.parse::<AudioFiles>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn taken_ids_are_rejected() {
+        let mut registry = DynamicAudioRegistry::default();
+        let laser = DynamicAudio::from_path("mods/laser.ogg");
+        registry.entries.insert(
+            laser,
+            DynamicAudioEntry {
+                path: "mods/laser.ogg".to_string(),
+                handle: Handle::default(),
+                duration: None,
+            },
+        );
+        assert!(registry.is_free(laser, "mods/laser.ogg"));
+        // A different path with the same hash
+        assert!(!registry.is_free(laser, "mods/other.ogg"));
+        // Generated files, and 0 which is reserved for Unknown
+        assert!(!registry.is_free(DynamicAudio(0), "mods/zero.ogg"));
+        for file in ALL_FILES {
+            assert!(!registry.is_free(DynamicAudio(file.id()), "mods/other.ogg"));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let files: Vec<AudioFiles> = ALL_FILES
+            .into_iter()
+            .chain([
+                AudioFiles::Unknown,
+                AudioFiles::Dynamic(DynamicAudio::from_path("mods/laser.ogg")),
+            ])
+            .collect();
+        let serialized = ron::to_string(&files).unwrap();
+        assert_eq!(
+            ron::from_str::<Vec<AudioFiles>>(&serialized).unwrap(),
+            files
+        );
+    }
+}
@@ -0,0 +1,415 @@
+use std::fmt;
+
+use bevy::{
+    audio::{AudioSink, AudioSinkPlayback, PlaybackSettings},
+    ecs::{
+        change_detection::DetectChanges,
+        component::Component,
+        entity::Entity,
+        event::{EventReader, EventWriter},
+        query::{Added, With, Without},
+        system::{Query, Res, ResMut, Resource},
+    },
+    log::warn,
+    time::Time,
+    utils::hashbrown::HashMap,
+};
+#[cfg(feature = "inspect")]
+use bevy::{
+    ecs::reflect::{ReflectComponent, ReflectResource},
+    reflect::Reflect,
+};
+
+use crate::{
+    audio_files::AudioFiles,
+    bounds::ACBounds,
+    channel::PlayContext,
+    delay_mode::DelayMode,
+    events::{AudioError, AudioErrorKind, PlayEvent, SettingsEvent},
+    global::GlobalChannel,
+    helpers,
+    resources::{AudioCache, ChannelSettings},
+};
+
+/// Type alias for the PlayEvent of dynamic channels, set the channel with [PlayEvent::on_channel]
+pub type DynamicPlayEvent = PlayEvent<DynamicChannel>;
+
+/// Type alias for the SettingsEvent of dynamic channels, set the channel with [SettingsEvent::on_channel]
+pub type DynamicSettingsEvent = SettingsEvent<DynamicChannel>;
+
+/// A channel that is created at runtime and addressed by name, e.g. from mods or a data driven UI
+///
+/// It is the 64-bit FNV-1a hash of the name, so it is stable between runs.
+/// Typed channels can be addressed the same way by their type name, e.g. `DynamicChannel::new("SfxChannel")`
+///
+/// # Example
+/// ```ignore
+/// use bevy::prelude::*;
+/// use bevy_audio_controller::prelude::*;
+///
+/// fn setup(mut channels: ResMut<DynamicChannels>, mut ew: EventWriter<DynamicPlayEvent>) {
+///     let ambient = channels.create("ambient");
+///     channels.settings_mut(ambient).unwrap().set_channel_volume(0.5);
+///     ew.send(DynamicPlayEvent::new(AudioFiles::WindOGG).on_channel(ambient));
+/// }
+/// ```
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+#[cfg_attr(feature = "inspect", reflect(Component))]
+pub struct DynamicChannel(u64);
+
+impl DynamicChannel {
+    /// Creates the id for a channel name, this does not create the channel
+    pub fn new(name: &str) -> Self {
+        Self(helpers::fnv1a(name))
+    }
+
+    /// Returns the numeric id
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+
+    /// Creates a [DynamicPlayEvent] for this channel
+    pub fn play_event(self, id: AudioFiles) -> DynamicPlayEvent {
+        DynamicPlayEvent::new(id).on_channel(self)
+    }
+
+    /// Creates a [DynamicSettingsEvent] for this channel
+    pub fn settings_event(self) -> DynamicSettingsEvent {
+        DynamicSettingsEvent::new().on_channel(self)
+    }
+}
+
+impl From<&str> for DynamicChannel {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<&String> for DynamicChannel {
+    fn from(name: &String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl fmt::Display for DynamicChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "channel:{:016x}", self.0)
+    }
+}
+
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+struct DynamicChannelEntry {
+    name: String,
+    settings: ChannelSettings<DynamicChannel>,
+}
+
+/// The cooldowns of every [DynamicChannel], kept apart so ticking them does not change [DynamicChannels]
+#[derive(Default, Resource)]
+pub(super) struct DynamicAudioCaches(HashMap<DynamicChannel, AudioCache<DynamicChannel>>);
+
+/// Holds the settings of every [DynamicChannel], and the names of the typed channels
+///
+/// Settings work the same as the [ChannelSettings] of a typed channel,
+/// they can be changed directly or with a [DynamicSettingsEvent]
+#[derive(Default, Resource)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+#[cfg_attr(feature = "inspect", reflect(Resource))]
+pub struct DynamicChannels {
+    channels: HashMap<DynamicChannel, DynamicChannelEntry>,
+    /// Typed channels by their short type name, the value is the full type name
+    typed: HashMap<DynamicChannel, &'static str>,
+}
+
+impl DynamicChannels {
+    /// Creates a channel and returns its id, creating a channel that already exists keeps its settings
+    ///
+    /// The channel starts out with the track settings from your audio config file that name it
+    pub fn create(&mut self, name: &str) -> DynamicChannel {
+        let channel = DynamicChannel::new(name);
+        if self.typed.contains_key(&channel) {
+            warn!("{} is already the name of a typed channel", name);
+            return channel;
+        }
+        self.channels
+            .entry(channel)
+            .or_insert_with(|| DynamicChannelEntry {
+                name: name.to_string(),
+                settings: ChannelSettings::from_config(|config| config.channel == Some(name)),
+            });
+        channel
+    }
+
+    /// Removes a channel along with its cooldowns, tracks that are playing on it keep playing, returns false if it did not exist
+    pub fn remove(&mut self, channel: impl Into<DynamicChannel>) -> bool {
+        self.channels.remove(&channel.into()).is_some()
+    }
+
+    /// Returns the channel with the given name, including typed channels
+    pub fn from_name(&self, name: &str) -> Option<DynamicChannel> {
+        let channel = DynamicChannel::new(name);
+        self.contains(channel).then_some(channel)
+    }
+
+    /// Returns the name of a channel, for typed channels this is the short type name
+    pub fn name(&self, channel: impl Into<DynamicChannel>) -> Option<&str> {
+        let channel = channel.into();
+        self.channels
+            .get(&channel)
+            .map(|entry| entry.name.as_str())
+            .or_else(|| {
+                self.typed
+                    .get(&channel)
+                    .map(|type_name| short_name(type_name))
+            })
+    }
+
+    /// Returns the full type name of a typed channel
+    pub fn type_name(&self, channel: impl Into<DynamicChannel>) -> Option<&'static str> {
+        self.typed.get(&channel.into()).copied()
+    }
+
+    /// Returns whether the channel exists, including typed channels
+    pub fn contains(&self, channel: impl Into<DynamicChannel>) -> bool {
+        let channel = channel.into();
+        self.channels.contains_key(&channel) || self.typed.contains_key(&channel)
+    }
+
+    /// Returns whether the channel is a typed channel
+    pub fn is_typed(&self, channel: impl Into<DynamicChannel>) -> bool {
+        self.typed.contains_key(&channel.into())
+    }
+
+    /// Returns the settings of a dynamic channel, typed channels use their own [ChannelSettings] resource
+    pub fn settings(
+        &self,
+        channel: impl Into<DynamicChannel>,
+    ) -> Option<&ChannelSettings<DynamicChannel>> {
+        self.channels
+            .get(&channel.into())
+            .map(|entry| &entry.settings)
+    }
+
+    /// Returns the settings of a dynamic channel for changing them
+    pub fn settings_mut(
+        &mut self,
+        channel: impl Into<DynamicChannel>,
+    ) -> Option<&mut ChannelSettings<DynamicChannel>> {
+        self.channels
+            .get_mut(&channel.into())
+            .map(|entry| &mut entry.settings)
+    }
+
+    /// Iterates over the dynamic channels and their names
+    pub fn iter(&self) -> impl Iterator<Item = (DynamicChannel, &str)> {
+        self.channels
+            .iter()
+            .map(|(channel, entry)| (*channel, entry.name.as_str()))
+    }
+
+    /// Iterates over the typed channels and their short type names
+    pub fn iter_typed(&self) -> impl Iterator<Item = (DynamicChannel, &str)> {
+        self.typed
+            .iter()
+            .map(|(channel, type_name)| (*channel, short_name(type_name)))
+    }
+
+    /// Typed channels are addressed by their short type name, so the first channel keeps a name that is taken twice
+    pub(super) fn register_typed<Channel: ACBounds>(&mut self) -> DynamicChannel {
+        let type_name = std::any::type_name::<Channel>();
+        let channel = DynamicChannel::new(short_name(type_name));
+        match self.typed.get(&channel) {
+            Some(existing) if *existing != type_name => warn!(
+                "{} and {} have the same name, dynamic events for {} go to {}",
+                existing,
+                type_name,
+                short_name(type_name),
+                existing
+            ),
+            Some(_) => {}
+            None => {
+                self.typed.insert(channel, type_name);
+            }
+        }
+        channel
+    }
+
+    /// Returns the channel a typed channel is addressed by, `None` if another channel took its name
+    fn typed_channel<Channel: ACBounds>(&self) -> Option<DynamicChannel> {
+        let type_name = std::any::type_name::<Channel>();
+        let channel = DynamicChannel::new(short_name(type_name));
+        (self.typed.get(&channel) == Some(&type_name)).then_some(channel)
+    }
+}
+
+pub(super) fn short_name(type_name: &str) -> &str {
+    type_name.rsplit("::").next().unwrap_or(type_name)
+}
+
+/// Forwards dynamic events that are addressed to a typed channel by name
+pub(super) fn forward_play_events<Channel: ACBounds>(
+    mut events: EventReader<DynamicPlayEvent>,
+    mut ew: EventWriter<PlayEvent<Channel>>,
+    channels: Res<DynamicChannels>,
+) {
+    let Some(channel) = channels.typed_channel::<Channel>() else {
+        return;
+    };
+    for event in events.read() {
+        if event.channel == Some(channel) {
+            ew.send(event.retype());
+        }
+    }
+}
+
+pub(super) fn forward_settings_events<Channel: ACBounds>(
+    mut events: EventReader<DynamicSettingsEvent>,
+    mut ew: EventWriter<SettingsEvent<Channel>>,
+    channels: Res<DynamicChannels>,
+) {
+    let Some(channel) = channels.typed_channel::<Channel>() else {
+        return;
+    };
+    for event in events.read() {
+        if event.channel == Some(channel) {
+            ew.send(event.retype());
+        }
+    }
+}
+
+pub(super) fn tick_dynamic_caches(
+    mut caches: ResMut<DynamicAudioCaches>,
+    channels: Res<DynamicChannels>,
+    time: Res<Time>,
+) {
+    // Drops the cooldowns of removed channels, so a channel created again under the same name starts fresh
+    if channels.is_changed() {
+        caches
+            .0
+            .retain(|channel, _| channels.channels.contains_key(channel));
+    }
+    for cache in caches.0.values_mut() {
+        cache.tick(time.delta());
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(super) fn dynamic_ecs_system(
+    query: Query<
+        (
+            Entity,
+            &AudioFiles,
+            Option<&PlaybackSettings>,
+            &DelayMode,
+            &DynamicChannel,
+        ),
+        (Added<DynamicChannel>, Without<AudioSink>),
+    >,
+    mut ew: EventWriter<DynamicPlayEvent>,
+) {
+    let mut events = Vec::new();
+    for (entity, audio_file, settings, mode, channel) in query.iter() {
+        let event = DynamicPlayEvent::new(*audio_file)
+            .on_channel(*channel)
+            .with_entity(entity)
+            .with_delay_mode(*mode);
+        if let Some(settings) = settings {
+            events.push(event.with_settings(*settings));
+        } else {
+            events.push(event);
+        }
+    }
+    ew.send_batch(events);
+}
+
+pub(super) fn dynamic_play_event_reader(
+    mut ctx: PlayContext,
+    mut events: EventReader<DynamicPlayEvent>,
+    channels: Res<DynamicChannels>,
+    mut caches: ResMut<DynamicAudioCaches>,
+) {
+    for event in events.read() {
+        let Some(channel) = event.channel else {
+            ctx.send_error(AudioError {
+                id: event.id,
+                channel: None,
+                kind: AudioErrorKind::UnregisteredChannel,
+                fallback: None,
+            });
+            continue;
+        };
+        if channels.typed.contains_key(&channel) {
+            continue;
+        }
+        let Some(entry) = channels.channels.get(&channel) else {
+            ctx.send_error(AudioError {
+                id: event.id,
+                channel: Some(channel.to_string()),
+                kind: AudioErrorKind::UnregisteredChannel,
+                fallback: None,
+            });
+            continue;
+        };
+        let cache = caches.0.entry(channel).or_default();
+        ctx.play(event, &entry.settings, cache, channel, &entry.name);
+    }
+}
+
+pub(super) fn dynamic_settings_event_reader(
+    mut channels: ResMut<DynamicChannels>,
+    mut events: EventReader<DynamicSettingsEvent>,
+) {
+    for event in events.read() {
+        let Some(channel) = event.channel else {
+            warn!("DynamicSettingsEvent without a channel, set it with `on_channel`");
+            continue;
+        };
+        if channels.typed.contains_key(&channel) {
+            continue;
+        }
+        if let Some(settings) = channels.settings_mut(channel) {
+            settings.apply_event(event);
+        } else {
+            warn!(
+                "Unable to change the settings of {}, it has not been created",
+                channel
+            );
+        }
+    }
+}
+
+pub(super) fn update_dynamic_track_volumes(
+    channels: Res<DynamicChannels>,
+    global: Res<ChannelSettings<GlobalChannel>>,
+    track_query: Query<(
+        &AudioSink,
+        &AudioFiles,
+        &DynamicChannel,
+        Option<&PlaybackSettings>,
+    )>,
+) {
+    for (sink, id, channel, playback) in track_query.iter() {
+        if let Some(settings) = channels.settings(*channel) {
+            let volume = helpers::get_normalized_volume(settings, &global);
+            sink.set_volume(volume * helpers::get_track_volume(playback, settings, id));
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(super) fn update_dynamic_volume_on_insert(
+    channels: Res<DynamicChannels>,
+    global: Res<ChannelSettings<GlobalChannel>>,
+    sink_query: Query<
+        (&AudioSink, &DynamicChannel, Option<&AudioFiles>),
+        (Added<AudioSink>, With<DynamicChannel>),
+    >,
+) {
+    for (sink, channel, id) in sink_query.iter() {
+        if let Some(settings) = channels.settings(*channel) {
+            let volume = helpers::get_normalized_volume(settings, &global);
+            let gain = id.map_or(1.0, |id| settings.get_track_gain(id));
+            sink.set_volume(sink.volume() * gain * volume);
+        }
+    }
+}
@@ -1,27 +1,34 @@
-use std::marker::PhantomData;
+use std::{fmt, marker::PhantomData, time::Duration};
 
 use bevy::{
+    asset::{io::AssetReaderError, AssetLoadError},
     audio::PlaybackSettings,
     ecs::{entity::Entity, event::Event},
 };
 
-use crate::{audio_files::AudioFiles, bounds::ACBounds, delay_mode::DelayMode};
+use crate::{
+    audio_files::AudioFiles, bounds::ACBounds, delay_mode::DelayMode,
+    dynamic_channel::DynamicChannel, loading::AudioGroup,
+};
 
 /// An event for playing an audio file on a channel
 ///
 /// # Example
-/// ```
+/// ```no_run
+/// use bevy::prelude::*;
 /// use bevy_audio_controller::prelude::*;
 ///
 /// fn main() {
 ///     App::new()
-///         .add_plugins(AudioControllerPlugin)
+///         .add_plugins(AudioControllerPlugin::default())
 ///         .add_systems(Update, play)
 ///         .run();
 /// }
 ///
 /// fn play(mut play_ew: EventWriter<GlobalPlayEvent>) {
-///     let event = GlobalPlayEvent::new(AudioFiles::FireOGG).with_settings(PlaybackSettings::DESPAWN);
+///     // Same as `AudioFiles::FireOGG` when fire.ogg is in your assets folder at build time
+///     let fire: AudioFiles = "fire.ogg".parse().unwrap_or_default();
+///     let event = GlobalPlayEvent::new(fire).with_settings(PlaybackSettings::DESPAWN);
 ///     play_ew.send(event);
 /// }
 /// ```
@@ -33,6 +40,14 @@ pub struct PlayEvent<T: ACBounds> {
     pub(super) child: bool,
     pub(super) settings: Option<PlaybackSettings>,
     pub(super) delay_mode: Option<DelayMode>,
+    pub(super) intro_loop: Option<bool>,
+    pub(super) start_offset: Option<Duration>,
+    /// Set when the delay was already checked, e.g. by [AudioController](crate::controller::AudioController)
+    pub(super) reserved: bool,
+    /// Set when the entity was spawned for this event, so it is despawned if the track can not play
+    pub(super) spawned: bool,
+    /// Only set for [DynamicChannel] events
+    pub(super) channel: Option<DynamicChannel>,
     _marker: PhantomData<T>,
 }
 
@@ -44,7 +59,12 @@ impl<T: ACBounds> PlayEvent<T> {
             entity: None,
             settings: None,
             delay_mode: None,
+            intro_loop: None,
+            start_offset: None,
             child: false,
+            reserved: false,
+            spawned: false,
+            channel: None,
             _marker: PhantomData::<T>,
         }
     }
@@ -69,6 +89,23 @@ impl<T: ACBounds> PlayEvent<T> {
         self
     }
 
+    /// Play the intro of the track once, then loop seamlessly between the [LoopPoints](crate::audio_files::LoopPoints) of the file,
+    /// overrides the default and channel settings
+    ///
+    /// Tracks without loop points play as usual
+    pub fn with_intro_loop(mut self, intro_loop: bool) -> Self {
+        self.intro_loop = Some(intro_loop);
+        self
+    }
+
+    /// Start playing the track part way in, e.g. to resume music after a scene change or to randomize where ambient loops start
+    ///
+    /// The delay of the channel only counts the part of the track that is played
+    pub fn with_start_offset(mut self, offset: Duration) -> Self {
+        self.start_offset = Some(offset);
+        self
+    }
+
     /// Set the audio to play as a child of the entity
     ///
     /// `with_entity` must be called before this otherwise it will panic
@@ -79,6 +116,110 @@ impl<T: ACBounds> PlayEvent<T> {
         self.child = true;
         self
     }
+
+    /// A copy of the event to try again next frame, e.g. while its file is still loading
+    pub(super) fn deferred(&self, id: AudioFiles) -> Self {
+        Self {
+            id,
+            reserved: true,
+            channel: self.channel,
+            ..self.retype()
+        }
+    }
+
+    pub(super) fn retype<U: ACBounds>(&self) -> PlayEvent<U> {
+        PlayEvent {
+            id: self.id,
+            entity: self.entity,
+            child: self.child,
+            settings: self.settings,
+            delay_mode: self.delay_mode,
+            intro_loop: self.intro_loop,
+            start_offset: self.start_offset,
+            reserved: self.reserved,
+            spawned: self.spawned,
+            channel: None,
+            _marker: PhantomData::<U>,
+        }
+    }
+}
+
+impl PlayEvent<DynamicChannel> {
+    /// Sets the channel to play the audio on, either a [DynamicChannel] or the name of a dynamic or typed channel
+    pub fn on_channel(mut self, channel: impl Into<DynamicChannel>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+}
+
+/// An observer trigger for playing an audio file on a channel in the same frame, instead of waiting for the [PlayEvent] reader in `PostUpdate`
+///
+/// When it targets an entity the audio is played on that entity, otherwise a new one is spawned
+///
+/// # Example
+/// ```ignore
+/// use bevy::prelude::*;
+/// use bevy_audio_controller::prelude::*;
+///
+/// fn on_explode(trigger: Trigger<Explode>, mut commands: Commands) {
+///     commands.trigger_targets(PlayAudio::<SfxChannel>::new(AudioFiles::ExplosionOGG), trigger.entity());
+///     commands.trigger(PlayAudio::<SfxChannel>::new(AudioFiles::FireOGG));
+/// }
+/// ```
+#[derive(Event)]
+pub struct PlayAudio<T: ACBounds> {
+    pub(super) event: PlayEvent<T>,
+}
+
+impl<T: ACBounds> PlayAudio<T> {
+    /// Create a new PlayAudio trigger with the given audio file
+    pub fn new(id: AudioFiles) -> Self {
+        Self {
+            event: PlayEvent::new(id),
+        }
+    }
+
+    /// Specify the [PlaybackSettings] for the track, overrides the default and channel settings
+    pub fn with_settings(mut self, settings: PlaybackSettings) -> Self {
+        self.event.settings = Some(settings);
+        self
+    }
+
+    /// Set the delay mode for the track, overrides the default and channel settings
+    pub fn with_delay_mode(mut self, delay_mode: DelayMode) -> Self {
+        self.event.delay_mode = Some(delay_mode);
+        self
+    }
+
+    /// See [PlayEvent::with_intro_loop]
+    pub fn with_intro_loop(mut self, intro_loop: bool) -> Self {
+        self.event.intro_loop = Some(intro_loop);
+        self
+    }
+
+    /// See [PlayEvent::with_start_offset]
+    pub fn with_start_offset(mut self, offset: Duration) -> Self {
+        self.event.start_offset = Some(offset);
+        self
+    }
+
+    /// Play the audio as a child of the targeted entity
+    pub fn as_child(mut self) -> Self {
+        self.event.child = true;
+        self
+    }
+}
+
+impl<T: ACBounds> From<PlayEvent<T>> for PlayAudio<T> {
+    fn from(event: PlayEvent<T>) -> Self {
+        Self { event }
+    }
+}
+
+impl<T: ACBounds> From<AudioFiles> for PlayAudio<T> {
+    fn from(id: AudioFiles) -> Self {
+        Self::new(id)
+    }
 }
 
 impl<Channel: ACBounds> From<AudioFiles> for PlayEvent<Channel> {
@@ -92,16 +233,21 @@ impl<Channel: ACBounds> From<AudioFiles> for PlayEvent<Channel> {
 /// Including track specific settings
 ///
 /// # Example
-/// ```
+/// ```no_run
+/// use bevy::prelude::*;
 /// use bevy_audio_controller::prelude::*;
 ///
 /// fn main() {
 ///     App::new()
-///         .add_plugins(AudioControllerPlugin)
+///         .add_plugins(AudioControllerPlugin::default())
+///         .register_audio_channel::<SfxChannel>()
 ///         .add_systems(Startup, setup)
 ///         .run();
 /// }
 ///
+/// #[derive(Component, Default, AudioChannel)]
+/// struct SfxChannel;
+///
 /// fn setup(mut ew: EventWriter<SettingsEvent<SfxChannel>>) {
 ///     // Set the volume for the channel
 ///     let vol_event = SfxChannel::settings_event().with_volume(0.5);
@@ -115,10 +261,11 @@ impl<Channel: ACBounds> From<AudioFiles> for PlayEvent<Channel> {
 ///         .with_settings(PlaybackSettings::REMOVE)
 ///         .all();
 ///
-///     // Set the playback settings for a specific track in the channel
+///     // Set the playback settings for a specific track in the channel, e.g. `AudioFiles::BackgroundOGG`
+///     let background: AudioFiles = "background.ogg".parse().unwrap_or_default();
 ///     let track_settings_event = SfxChannel::settings_event()
 ///         .with_settings(PlaybackSettings::LOOP)
-///         .with_track(AudioFiles::BackgroundOGG);
+///         .with_track(background);
 ///
 ///     ew.send_batch(vec![
 ///         vol_event,
@@ -132,20 +279,37 @@ impl<Channel: ACBounds> From<AudioFiles> for PlayEvent<Channel> {
 pub struct SettingsEvent<Channel: ACBounds> {
     pub(super) settings: Option<PlaybackSettings>,
     pub(super) volume: Option<f32>,
+    pub(super) muted: Option<bool>,
+    pub(super) normalization_target: Option<Option<f32>>,
     pub(super) track: Option<AudioFiles>,
     pub(super) delay_mode: Option<DelayMode>,
+    pub(super) intro_loop: Option<bool>,
+    pub(super) fallback: Option<Option<AudioFiles>>,
     pub(super) all: bool,
+    /// Only set for [DynamicChannel] events
+    pub(super) channel: Option<DynamicChannel>,
     _marker: PhantomData<Channel>,
 }
 
+impl<Channel: ACBounds> Default for SettingsEvent<Channel> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<Channel: ACBounds> SettingsEvent<Channel> {
     pub fn new() -> Self {
         Self {
             track: None,
             settings: None,
             volume: None,
+            muted: None,
+            normalization_target: None,
             delay_mode: None,
+            intro_loop: None,
+            fallback: None,
             all: false,
+            channel: None,
             _marker: PhantomData::<Channel>,
         }
     }
@@ -156,6 +320,24 @@ impl<Channel: ACBounds> SettingsEvent<Channel> {
         self
     }
 
+    /// Mutes or unmutes the channel
+    pub fn with_muted(mut self, muted: bool) -> Self {
+        self.muted = Some(muted);
+        self
+    }
+
+    /// Normalizes every track in the channel to the given loudness in LUFS, e.g. -16.0
+    pub fn with_normalization_target(mut self, target: f32) -> Self {
+        self.normalization_target = Some(Some(target));
+        self
+    }
+
+    /// Disables loudness normalization for the channel
+    pub fn without_normalization(mut self) -> Self {
+        self.normalization_target = Some(None);
+        self
+    }
+
     /// When called on its own without `with_track` or `all`, this sets the default [PlaybackSettings] for the channel
     pub fn with_settings(mut self, settings: PlaybackSettings) -> Self {
         self.settings = Some(settings);
@@ -168,6 +350,25 @@ impl<Channel: ACBounds> SettingsEvent<Channel> {
         self
     }
 
+    /// When called on its own without `with_track` or `all`, this sets whether tracks in the channel
+    /// play their intro once and then loop between their [LoopPoints](crate::audio_files::LoopPoints) by default
+    pub fn with_intro_loop(mut self, intro_loop: bool) -> Self {
+        self.intro_loop = Some(intro_loop);
+        self
+    }
+
+    /// Sets a file that the channel plays instead when the requested file can not be played, see [AudioError]
+    pub fn with_fallback(mut self, fallback: AudioFiles) -> Self {
+        self.fallback = Some(Some(fallback));
+        self
+    }
+
+    /// Removes the fallback file of the channel
+    pub fn without_fallback(mut self) -> Self {
+        self.fallback = Some(None);
+        self
+    }
+
     /// Instead applies the specified setting or delay_mode to a specific track
     pub fn with_track(mut self, id: AudioFiles) -> Self {
         if self.all {
@@ -185,4 +386,120 @@ impl<Channel: ACBounds> SettingsEvent<Channel> {
         self.all = true;
         self
     }
+
+    pub(super) fn retype<U: ACBounds>(&self) -> SettingsEvent<U> {
+        SettingsEvent {
+            settings: self.settings,
+            volume: self.volume,
+            muted: self.muted,
+            normalization_target: self.normalization_target,
+            track: self.track,
+            delay_mode: self.delay_mode,
+            intro_loop: self.intro_loop,
+            fallback: self.fallback,
+            all: self.all,
+            channel: None,
+            _marker: PhantomData::<U>,
+        }
+    }
+}
+
+impl SettingsEvent<DynamicChannel> {
+    /// Sets the channel to change, either a [DynamicChannel] or the name of a dynamic or typed channel
+    pub fn on_channel(mut self, channel: impl Into<DynamicChannel>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+}
+
+/// An event for loading a group of audio files, e.g. the sounds of the next level
+///
+/// # Example
+/// ```ignore
+/// fn enter_level(mut ew: EventWriter<LoadAudioGroup>) {
+///     ew.send(LoadAudioGroup(AudioGroup::Folder("levels/forest".to_string())));
+/// }
+/// ```
+#[derive(Event)]
+pub struct LoadAudioGroup(pub AudioGroup);
+
+/// An event for unloading a group of audio files, tracks that are currently playing keep playing until they finish
+#[derive(Event)]
+pub struct UnloadAudioGroup(pub AudioGroup);
+
+/// Sent whenever an audio file can not be played, useful for making missing assets obvious in QA builds
+///
+/// # Example
+/// ```ignore
+/// fn report_audio_errors(mut events: EventReader<AudioError>) {
+///     for error in events.read() {
+///         error!("{}", error);
+///     }
+/// }
+/// ```
+#[derive(Event, Debug, Clone)]
+pub struct AudioError {
+    /// The file that was requested
+    pub id: AudioFiles,
+    /// The name of the channel the file was played on, `None` if the error happened while loading
+    pub channel: Option<String>,
+    pub kind: AudioErrorKind,
+    /// The fallback file of the channel that was played instead, if any
+    pub fallback: Option<AudioFiles>,
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unable to play {}", self.id)?;
+        if let Some(channel) = &self.channel {
+            write!(f, " on {}", channel)?;
+        }
+        write!(f, ": {}", self.kind)?;
+        if let Some(fallback) = self.fallback {
+            write!(f, ", playing {} instead", fallback)?;
+        }
+        Ok(())
+    }
+}
+
+/// The reason an [AudioError] was sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AudioErrorKind {
+    /// [AudioFiles::Unknown] was requested, usually from parsing a path that is not in your assets folder
+    UnknownFile,
+    /// The file has not been loaded, e.g. with [crate::loading::LoadStrategy::Manual] or an unregistered dynamic file
+    NotLoaded,
+    /// The file could not be found when loading it
+    MissingFile,
+    /// The file was found but could not be loaded or decoded
+    DecodeFailed(String),
+    /// The channel has not been registered with [crate::channel::ChannelRegistration::register_audio_channel]
+    /// or created with [crate::dynamic_channel::DynamicChannels::create]
+    ///
+    /// Sent for [PlayEvent]s of dynamic channels and [PlayAudioExt::play_audio](crate::commands::PlayAudioExt::play_audio),
+    /// a system with an `EventWriter` for the [PlayEvent] of an unregistered channel panics like it would for any other event
+    UnregisteredChannel,
+}
+
+impl fmt::Display for AudioErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioErrorKind::UnknownFile => write!(f, "unknown file"),
+            AudioErrorKind::NotLoaded => write!(f, "file is not loaded"),
+            AudioErrorKind::MissingFile => write!(f, "file is missing"),
+            AudioErrorKind::DecodeFailed(error) => write!(f, "failed to load: {}", error),
+            AudioErrorKind::UnregisteredChannel => write!(f, "channel is not registered"),
+        }
+    }
+}
+
+impl From<&AssetLoadError> for AudioErrorKind {
+    fn from(error: &AssetLoadError) -> Self {
+        match error {
+            AssetLoadError::AssetReaderError(AssetReaderError::NotFound(_)) => {
+                AudioErrorKind::MissingFile
+            }
+            error => AudioErrorKind::DecodeFailed(error.to_string()),
+        }
+    }
 }
@@ -1,10 +1,84 @@
-use bevy::ecs::system::Res;
+use std::time::Duration;
 
-use crate::{bounds::ACBounds, global::GlobalChannel, resources::ChannelSettings};
+use bevy::audio::PlaybackSettings;
+
+use crate::{
+    audio_files::AudioFiles, bounds::ACBounds, dynamic::DynamicAudioRegistry,
+    global::GlobalChannel, resources::ChannelSettings,
+};
+
+/// 64-bit FNV-1a, used for ids that have to be stable between runs
+pub(crate) fn fnv1a(value: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    value.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
 
 pub fn get_normalized_volume<Channel: ACBounds>(
-    channel: &Res<ChannelSettings<Channel>>,
-    global: &Res<ChannelSettings<GlobalChannel>>,
+    channel: &ChannelSettings<Channel>,
+    global: &ChannelSettings<GlobalChannel>,
+) -> f32 {
+    channel.get_effective_volume() * global.get_effective_volume()
+}
+
+/// The volume of a single track before the channel volume is applied
+///
+/// Uses the [PlaybackSettings] the track was played with, the normalization gain is applied on top
+/// so a new normalization target also changes tracks that are already playing
+pub(crate) fn get_track_volume<Channel: ACBounds>(
+    playback: Option<&PlaybackSettings>,
+    channel: &ChannelSettings<Channel>,
+    id: &AudioFiles,
 ) -> f32 {
-    channel.get_channel_volume() * global.get_channel_volume()
+    let volume = playback.map_or_else(
+        || channel.get_track_setting(id).volume.get(),
+        |playback| playback.volume.get(),
+    );
+    volume * channel.get_track_gain(id)
+}
+
+/// The duration of a file in seconds, 0.0 for dynamic files that have not been loaded yet
+pub(crate) fn get_track_duration(id: &AudioFiles, dynamic_registry: &DynamicAudioRegistry) -> f32 {
+    match id {
+        AudioFiles::Dynamic(_) => dynamic_registry.get_duration(id).unwrap_or_default(),
+        id => id.duration(),
+    }
+}
+
+/// The duration in seconds that is left of a file when it starts playing at `start_offset`
+pub(crate) fn get_played_duration(
+    id: &AudioFiles,
+    start_offset: Option<Duration>,
+    dynamic_registry: &DynamicAudioRegistry,
+) -> f32 {
+    let duration = get_track_duration(id, dynamic_registry);
+    let offset = start_offset.map_or(0.0, |offset| offset.as_secs_f32());
+    (duration - offset).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{audio_files::ALL_FILES, dynamic::DynamicAudio};
+
+    #[test]
+    fn fnv1a_matches_the_test_vectors() {
+        assert_eq!(fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a("foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn generated_ids_match_runtime_ids() {
+        for file in ALL_FILES {
+            let path = file.to_string();
+            assert_eq!(file.id(), fnv1a(&path), "{}", path);
+            assert_eq!(DynamicAudio::from_path(&path).id(), file.id());
+            assert_eq!(AudioFiles::from_id(file.id()), Some(file));
+        }
+        assert_eq!(AudioFiles::Unknown.id(), 0);
+        assert_eq!(AudioFiles::from_id(0), Some(AudioFiles::Unknown));
+    }
 }
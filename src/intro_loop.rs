@@ -0,0 +1,207 @@
+use std::time::Duration;
+
+use bevy::{
+    asset::{Asset, Assets, Handle},
+    audio::{AudioSource, Decodable, Source},
+    ecs::{
+        system::{Commands, Resource},
+        world::World,
+    },
+    reflect::TypePath,
+    utils::hashbrown::HashMap,
+};
+
+use crate::audio_files::{AudioFiles, LoopPoints};
+
+type Sample = <<AudioSource as Decodable>::Decoder as Iterator>::Item;
+
+/// An audio source that plays its intro once and then loops between the [LoopPoints] of the file
+///
+/// Created by the plugin when a track is played with the intro loop enabled, see [crate::events::PlayEvent::with_intro_loop]
+#[derive(Asset, TypePath, Clone)]
+pub struct IntroLoopSource {
+    pub(super) source: AudioSource,
+    pub(super) loop_points: LoopPoints,
+}
+
+impl Decodable for IntroLoopSource {
+    type DecoderItem = Sample;
+    type Decoder = IntroLoopDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        let decoder = self.source.decoder();
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+        IntroLoopDecoder {
+            decoder: Some(decoder),
+            channels,
+            sample_rate,
+            position: 0,
+            start: self.loop_points.start * channels as u64,
+            end: self.loop_points.end * channels as u64,
+            loop_buffer: Vec::new(),
+            cursor: 0,
+        }
+    }
+}
+
+/// Streams the file up to the loop end while recording the loop region,
+/// then repeats the recorded region forever so the loop is seamless
+pub struct IntroLoopDecoder {
+    decoder: Option<<AudioSource as Decodable>::Decoder>,
+    channels: u16,
+    sample_rate: u32,
+    /// Interleaved samples read from the decoder so far
+    position: u64,
+    start: u64,
+    end: u64,
+    loop_buffer: Vec<Sample>,
+    cursor: usize,
+}
+
+impl Iterator for IntroLoopDecoder {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(decoder) = self.decoder.as_mut() {
+            if self.position < self.end {
+                if let Some(sample) = decoder.next() {
+                    if self.position >= self.start {
+                        self.loop_buffer.push(sample);
+                    }
+                    self.position += 1;
+                    return Some(sample);
+                }
+            }
+            // Reached the loop end (or the file ended early), only the recorded loop is needed from now on
+            self.decoder = None;
+        }
+
+        let sample = *self.loop_buffer.get(self.cursor)?;
+        self.cursor = (self.cursor + 1) % self.loop_buffer.len();
+        Some(sample)
+    }
+}
+
+impl Source for IntroLoopDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.decoder
+            .as_ref()
+            .and_then(|decoder| decoder.current_frame_len())
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Keeps one [IntroLoopSource] per track so they are only created once
+#[derive(Default, Resource)]
+pub(super) struct IntroLoopCache {
+    map: HashMap<AudioFiles, Handle<IntroLoopSource>>,
+}
+
+impl IntroLoopCache {
+    /// Returns `None` if the [AudioSource] of the track has not finished loading
+    ///
+    /// A new source gets a reserved handle right away, the source itself is added when the commands are applied
+    pub(super) fn get_or_queue(
+        &self,
+        commands: &mut Commands,
+        id: AudioFiles,
+        loop_points: LoopPoints,
+        source: &Handle<AudioSource>,
+        audio_sources: &Assets<AudioSource>,
+        intro_loops: &Assets<IntroLoopSource>,
+    ) -> Option<Handle<IntroLoopSource>> {
+        if let Some(handle) = self.map.get(&id) {
+            return Some(handle.clone());
+        }
+        let source = IntroLoopSource {
+            source: audio_sources.get(source)?.clone(),
+            loop_points,
+        };
+        let handle = intro_loops.reserve_handle();
+        let cached = handle.clone();
+        commands.queue(move |world: &mut World| {
+            world
+                .resource_mut::<Assets<IntroLoopSource>>()
+                .insert(&cached, source);
+            // Another channel may have created one in the same frame, the first one is kept
+            world
+                .resource_mut::<IntroLoopCache>()
+                .map
+                .entry(id)
+                .or_insert(cached);
+        });
+        Some(handle)
+    }
+
+    /// Drops the cached source of a track, it holds a copy of the audio file until every player of it is gone
+    pub(super) fn remove(&mut self, id: &AudioFiles) {
+        self.map.remove(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bevy::{
+        app::{App, Update},
+        asset::{AssetApp, AssetPlugin},
+        MinimalPlugins,
+    };
+
+    use super::*;
+    use crate::{
+        ac_assets::ACAssetLoader,
+        dynamic::DynamicAudio,
+        events::UnloadAudioGroup,
+        loading::{unload_group_reader, AudioGroup, AudioLoadingProgress},
+    };
+
+    #[test]
+    fn unloaded_groups_drop_their_intro_loops() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<IntroLoopSource>()
+            .init_resource::<ACAssetLoader>()
+            .init_resource::<AudioLoadingProgress>()
+            .init_resource::<IntroLoopCache>()
+            .add_event::<UnloadAudioGroup>()
+            .add_systems(Update, unload_group_reader);
+
+        let id = AudioFiles::Dynamic(DynamicAudio::from_path("music/theme.ogg"));
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<IntroLoopSource>>()
+            .add(IntroLoopSource {
+                source: AudioSource {
+                    bytes: Arc::from([0u8; 16]),
+                },
+                loop_points: LoopPoints::default(),
+            });
+        app.world_mut()
+            .resource_mut::<IntroLoopCache>()
+            .map
+            .insert(id, handle);
+        app.update();
+        assert_eq!(app.world().resource::<Assets<IntroLoopSource>>().len(), 1);
+
+        app.world_mut()
+            .send_event(UnloadAudioGroup(AudioGroup::Files(vec![id])));
+        app.update();
+        app.update();
+        assert!(app.world().resource::<IntroLoopCache>().map.is_empty());
+        assert!(app.world().resource::<Assets<IntroLoopSource>>().is_empty());
+    }
+}
@@ -11,12 +11,24 @@ use delay_mode::DelayMode;
 mod audio_channel;
 mod bounds;
 mod channel;
+mod commands;
+mod controller;
 mod delay_mode;
+mod dynamic;
+mod dynamic_channel;
 mod events;
 mod global;
 mod helpers;
+mod intro_loop;
+mod loading;
+mod offset;
 mod plugin;
+mod progress;
+mod registry;
 mod resources;
+mod routing;
+mod synth;
+mod validation;
 
 include!(concat!(env!("OUT_DIR"), "/audio_controller.rs"));
 
@@ -25,7 +37,7 @@ impl Component for AudioFiles {
 
     fn register_component_hooks(_hooks: &mut ComponentHooks) {
         _hooks.on_add(|mut world, entity, _| {
-            let val: AudioFiles = world.get::<Self>(entity).unwrap().clone();
+            let val: AudioFiles = *world.get::<Self>(entity).unwrap();
             debug!("Adding audio track: {:?}", val);
             if world.get::<DelayMode>(entity).is_none() {
                 world
@@ -43,7 +55,7 @@ impl Component for AudioFiles {
         });
 
         _hooks.on_remove(|mut world, entity, _| {
-            let val = world.get::<Self>(entity).unwrap().clone();
+            let val = *world.get::<Self>(entity).unwrap();
             debug!("Removing audio track: {:?}", val);
             if world.get::<DelayMode>(entity).is_none() {
                 world
@@ -57,15 +69,30 @@ impl Component for AudioFiles {
 }
 
 pub mod prelude {
-    pub use super::audio_channel::AudioChannel;
+    pub use super::audio_channel::{audio_channel_marker, AudioChannel};
     pub use super::audio_files::AudioFiles;
     pub use super::bounds::ACBounds;
     pub use super::channel::*;
+    pub use super::commands::{PlayAudioExt, SeekAudioExt};
+    pub use super::controller::{AudioController, PlaybackHandle};
     pub use super::delay_mode::*;
+    pub use super::dynamic::{DynamicAudio, DynamicAudioRegistry};
+    pub use super::dynamic_channel::{
+        DynamicChannel, DynamicChannels, DynamicPlayEvent, DynamicSettingsEvent,
+    };
     pub use super::events::*;
     pub use super::global::*;
+    pub use super::intro_loop::IntroLoopSource;
+    #[cfg(feature = "state")]
+    pub use super::loading::AudioLoadingStateExt;
+    pub use super::loading::{AudioGroup, AudioLoadingProgress, LoadStrategy, LoadingProgress};
     #[allow(unused)]
     pub use super::markers::*;
+    pub use super::offset::OffsetSource;
     pub use super::plugin::*;
+    pub use super::progress::PlaybackProgress;
+    pub use super::registry::{AudioChannelRegistry, ChannelInfo};
     pub use super::resources::*;
+    pub use super::synth::{Synth, SynthPreset, SynthSource, Waveform};
+    pub use super::validation::{AudioValidationPlugin, AudioValidationReport, DurationMismatch};
 }
@@ -0,0 +1,338 @@
+#[cfg(feature = "state")]
+use bevy::{
+    app::{App, PostUpdate},
+    ecs::schedule::IntoSystemConfigs,
+    state::{
+        condition::in_state,
+        state::{FreelyMutableState, NextState},
+    },
+};
+use bevy::{
+    asset::{AssetLoadFailedEvent, AssetServer, Handle, LoadState},
+    audio::AudioSource,
+    ecs::{
+        event::{EventReader, EventWriter},
+        system::{Commands, Res, ResMut, Resource},
+        world::World,
+    },
+    log::{debug, warn},
+};
+#[cfg(feature = "inspect")]
+use bevy::{ecs::reflect::ReflectResource, reflect::Reflect};
+
+use crate::{
+    ac_assets::ACAssetLoader,
+    audio_files::{AudioFiles, ALL_FILES},
+    dynamic::DynamicAudioRegistry,
+    events::{AudioError, LoadAudioGroup, UnloadAudioGroup},
+    intro_loop::IntroLoopCache,
+};
+
+/// Specifies when the audio files in your assets folder are loaded
+///
+/// Set it with [crate::plugin::AudioControllerPlugin::with_load_strategy]
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+#[cfg_attr(feature = "inspect", reflect(Resource))]
+pub enum LoadStrategy {
+    /// Loads every file at `Startup`
+    ///
+    /// This is the default behavior
+    #[default]
+    Eager,
+    /// Loads a file the first time it is played, that first playback starts as soon as the file has loaded
+    Lazy,
+    /// Only loads files through [LoadAudioGroup] events
+    ///
+    /// Playing a file that has not been loaded sends an [AudioError] with
+    /// [AudioErrorKind::NotLoaded](crate::events::AudioErrorKind::NotLoaded) and plays the fallback of the channel instead, if it has one
+    Manual,
+}
+
+/// A set of audio files that are loaded or unloaded together with [LoadAudioGroup] and [UnloadAudioGroup]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+pub enum AudioGroup {
+    /// Every file in your assets folder
+    All,
+    /// Every file in a folder and its subfolders, relative to your assets folder, e.g. `"sfx/weapons"`
+    Folder(String),
+    /// A specific list of files
+    Files(Vec<AudioFiles>),
+    /// Every file with this tag in the `audio.toml` or `audio.ron` file in your assets folder, e.g. `"weapon"`
+    Tag(String),
+}
+
+impl AudioGroup {
+    /// Returns the files that belong to this group
+    pub fn files(&self) -> Vec<AudioFiles> {
+        match self {
+            AudioGroup::All => ALL_FILES.to_vec(),
+            AudioGroup::Folder(folder) => {
+                let prefix = format!("{}/", folder.replace('\\', "/").trim_end_matches('/'));
+                ALL_FILES
+                    .into_iter()
+                    .filter(|id| id.path().replace('\\', "/").starts_with(&prefix))
+                    .collect()
+            }
+            AudioGroup::Files(files) => files.clone(),
+            AudioGroup::Tag(tag) => ALL_FILES
+                .into_iter()
+                .filter(|id| id.get().tags.contains(&tag.as_str()))
+                .collect(),
+        }
+    }
+}
+
+impl ACAssetLoader {
+    /// Starts loading the file if it has not been requested yet and returns its handle
+    pub(super) fn load(
+        &mut self,
+        asset_server: &AssetServer,
+        id: &AudioFiles,
+    ) -> Option<Handle<AudioSource>> {
+        let slot = self.get_slot(id)?;
+        Some(
+            slot.get_or_insert_with(|| {
+                debug!("Loading audio file: {:?}", id);
+                asset_server.load(id.path())
+            })
+            .clone(),
+        )
+    }
+
+    /// Same as [ACAssetLoader::load] for systems that only read the loader, the handle is stored when the commands are applied
+    pub(super) fn load_queued(
+        &self,
+        commands: &mut Commands,
+        asset_server: &AssetServer,
+        id: &AudioFiles,
+    ) -> Option<Handle<AudioSource>> {
+        if let Some(handle) = self.get(id) {
+            return Some(handle);
+        }
+        if matches!(id, AudioFiles::Unknown | AudioFiles::Dynamic(_)) {
+            return None;
+        }
+        debug!("Loading audio file: {:?}", id);
+        // The asset server hands out the same handle while the file is loading, so loads queued twice are harmless
+        let handle: Handle<AudioSource> = asset_server.load(id.path());
+        let stored = handle.clone();
+        let id = *id;
+        commands.queue(move |world: &mut World| {
+            if let Some(slot) = world.resource_mut::<ACAssetLoader>().get_slot(&id) {
+                slot.get_or_insert(stored);
+            }
+        });
+        Some(handle)
+    }
+
+    /// Drops the handle, the asset is freed once nothing else is playing it
+    pub(super) fn unload(&mut self, id: &AudioFiles) {
+        if let Some(slot) = self.get_slot(id) {
+            if slot.take().is_some() {
+                debug!("Unloading audio file: {:?}", id);
+            }
+        }
+    }
+}
+
+pub(super) fn load_all_assets(
+    asset_server: Res<AssetServer>,
+    mut asset_loader: ResMut<ACAssetLoader>,
+) {
+    for id in ALL_FILES {
+        asset_loader.load(&asset_server, &id);
+    }
+}
+
+pub(super) fn load_group_reader(
+    asset_server: Res<AssetServer>,
+    mut asset_loader: ResMut<ACAssetLoader>,
+    mut progress: ResMut<AudioLoadingProgress>,
+    mut events: EventReader<LoadAudioGroup>,
+) {
+    for event in events.read() {
+        for id in event.0.files() {
+            asset_loader.load(&asset_server, &id);
+        }
+        if !progress.groups.iter().any(|(group, _)| *group == event.0) {
+            progress
+                .groups
+                .push((event.0.clone(), LoadingProgress::default()));
+        }
+    }
+}
+
+pub(super) fn unload_group_reader(
+    mut asset_loader: ResMut<ACAssetLoader>,
+    mut progress: ResMut<AudioLoadingProgress>,
+    mut intro_loop_cache: ResMut<IntroLoopCache>,
+    mut events: EventReader<UnloadAudioGroup>,
+) {
+    for event in events.read() {
+        for id in event.0.files() {
+            asset_loader.unload(&id);
+            intro_loop_cache.remove(&id);
+        }
+        progress.groups.retain(|(group, _)| *group != event.0);
+    }
+}
+
+pub(super) fn report_failed_loads(
+    dynamic_registry: Res<DynamicAudioRegistry>,
+    mut events: EventReader<AssetLoadFailedEvent<AudioSource>>,
+    mut error_ew: EventWriter<AudioError>,
+) {
+    for event in events.read() {
+        let path = event.path.path().to_string_lossy();
+        let error = AudioError {
+            id: dynamic_registry
+                .from_path(&path)
+                .unwrap_or(AudioFiles::Unknown),
+            channel: None,
+            kind: (&event.error).into(),
+            fallback: None,
+        };
+        warn!("{} ({})", error, path);
+        error_ew.send(error);
+    }
+}
+
+/// Loaded, failed, and total counts for a set of audio files
+///
+/// Only files that have been requested and not unloaded since count towards the total
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+pub struct LoadingProgress {
+    pub loaded: usize,
+    pub failed: usize,
+    pub total: usize,
+}
+
+impl LoadingProgress {
+    /// Returns true once every file has either loaded or failed to load
+    pub fn is_done(&self) -> bool {
+        self.loaded + self.failed >= self.total
+    }
+
+    /// Returns the share of files that have finished, loaded or failed, on a scale of 0.0 - 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.loaded + self.failed) as f32 / self.total as f32
+        }
+    }
+
+    fn add(&mut self, state: Option<LoadState>) {
+        self.total += 1;
+        match state {
+            Some(LoadState::Loaded) => self.loaded += 1,
+            Some(LoadState::Failed(_)) => self.failed += 1,
+            _ => {}
+        }
+    }
+}
+
+/// Tracks how many of the requested audio files have loaded
+///
+/// `overall` covers every file that has been requested, whether by the [LoadStrategy] or a [LoadAudioGroup] event,
+/// and every loaded group is tracked on its own until it is unloaded
+#[derive(Resource, Default, Debug, Clone)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+#[cfg_attr(feature = "inspect", reflect(Resource))]
+pub struct AudioLoadingProgress {
+    overall: LoadingProgress,
+    groups: Vec<(AudioGroup, LoadingProgress)>,
+}
+
+impl AudioLoadingProgress {
+    /// Returns the progress of every requested file
+    pub fn overall(&self) -> LoadingProgress {
+        self.overall
+    }
+
+    /// Returns the progress of a group that was loaded with [LoadAudioGroup]
+    pub fn group(&self, group: &AudioGroup) -> Option<LoadingProgress> {
+        self.groups
+            .iter()
+            .find(|(tracked, _)| tracked == group)
+            .map(|(_, progress)| *progress)
+    }
+
+    /// Returns true once every requested file has either loaded or failed to load
+    pub fn is_done(&self) -> bool {
+        self.overall.is_done() && self.groups.iter().all(|(_, progress)| progress.is_done())
+    }
+}
+
+pub(super) fn update_loading_progress(
+    asset_server: Res<AssetServer>,
+    asset_loader: Res<ACAssetLoader>,
+    mut progress: ResMut<AudioLoadingProgress>,
+) {
+    let state = |id: &AudioFiles| {
+        asset_loader
+            .get(id)
+            .map(|handle| asset_server.get_load_state(&handle))
+    };
+
+    let mut overall = LoadingProgress::default();
+    for id in ALL_FILES {
+        if let Some(load_state) = state(&id) {
+            overall.add(load_state);
+        }
+    }
+
+    let groups = progress
+        .groups
+        .iter()
+        .map(|(group, _)| {
+            // Only files with a handle count, which leaves out dynamic files and files that another group unloaded
+            let mut group_progress = LoadingProgress::default();
+            for id in group.files() {
+                if let Some(load_state) = state(&id) {
+                    group_progress.add(load_state);
+                }
+            }
+            (group.clone(), group_progress)
+        })
+        .collect();
+
+    let new_progress = AudioLoadingProgress { overall, groups };
+    if progress.overall != new_progress.overall || progress.groups != new_progress.groups {
+        *progress = new_progress;
+    }
+}
+
+/// Adds a helper for holding a loading state until the requested audio has loaded
+#[cfg(feature = "state")]
+pub trait AudioLoadingStateExt {
+    /// While in the `loading` state, moves to the `next` state once [AudioLoadingProgress::is_done]
+    fn transition_when_audio_loaded<S: FreelyMutableState>(
+        &mut self,
+        loading: S,
+        next: S,
+    ) -> &mut Self;
+}
+
+#[cfg(feature = "state")]
+impl AudioLoadingStateExt for App {
+    fn transition_when_audio_loaded<S: FreelyMutableState>(
+        &mut self,
+        loading: S,
+        next: S,
+    ) -> &mut Self {
+        // PostUpdate so groups requested on entering the state have been counted
+        self.add_systems(
+            PostUpdate,
+            (move |progress: Res<AudioLoadingProgress>, mut next_state: ResMut<NextState<S>>| {
+                if progress.is_done() {
+                    next_state.set(next.clone());
+                }
+            })
+            .run_if(in_state(loading)),
+        )
+    }
+}
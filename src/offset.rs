@@ -0,0 +1,337 @@
+use std::time::Duration;
+
+use bevy::{
+    asset::{Asset, Assets},
+    audio::{
+        AudioPlayer, AudioSink, AudioSource, Decodable, PlaybackMode, PlaybackSettings, Source,
+    },
+    ecs::{entity::Entity, world::World},
+    log::warn,
+    reflect::TypePath,
+};
+
+use crate::{
+    audio_files::{AudioFiles, LoopPoints},
+    intro_loop::{IntroLoopDecoder, IntroLoopSource},
+    progress::PlaybackProgress,
+    synth::SynthSource,
+};
+
+type Sample = <<AudioSource as Decodable>::Decoder as Iterator>::Item;
+
+/// An audio source that starts part way into a file
+///
+/// Created by the plugin when a track is played with [crate::events::PlayEvent::with_start_offset] or seeked with
+/// [crate::commands::SeekAudioExt::seek_audio]
+#[derive(Asset, TypePath, Clone)]
+pub struct OffsetSource {
+    source: AudioSource,
+    loop_points: Option<LoopPoints>,
+    offset: Duration,
+    /// Plays the skipped part after the end, so looping the source loops the whole file
+    rotate: bool,
+}
+
+impl OffsetSource {
+    pub(super) fn new(
+        source: AudioSource,
+        loop_points: Option<LoopPoints>,
+        offset: Duration,
+        mode: PlaybackMode,
+    ) -> Self {
+        Self {
+            source,
+            loop_points,
+            offset,
+            rotate: loop_points.is_none() && matches!(mode, PlaybackMode::Loop),
+        }
+    }
+}
+
+impl Decodable for OffsetSource {
+    type DecoderItem = Sample;
+    type Decoder = OffsetDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        let inner = match self.loop_points {
+            Some(loop_points) => Inner::IntroLoop(
+                IntroLoopSource {
+                    source: self.source.clone(),
+                    loop_points,
+                }
+                .decoder(),
+            ),
+            None => Inner::File(self.source.decoder()),
+        };
+        let channels = inner.channels();
+        let sample_rate = inner.sample_rate();
+        let skip = (self.offset.as_secs_f64() * sample_rate as f64) as u64 * channels as u64;
+        OffsetDecoder {
+            inner,
+            source: self.rotate.then(|| self.source.clone()),
+            channels,
+            sample_rate,
+            offset: self.offset,
+            skip,
+            skipped: false,
+            remaining: None,
+        }
+    }
+}
+
+enum Inner {
+    File(<AudioSource as Decodable>::Decoder),
+    IntroLoop(IntroLoopDecoder),
+}
+
+impl Inner {
+    fn next(&mut self) -> Option<Sample> {
+        match self {
+            Inner::File(decoder) => decoder.next(),
+            Inner::IntroLoop(decoder) => decoder.next(),
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        match self {
+            Inner::File(decoder) => decoder.channels(),
+            Inner::IntroLoop(decoder) => decoder.channels(),
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        match self {
+            Inner::File(decoder) => decoder.sample_rate(),
+            Inner::IntroLoop(decoder) => decoder.sample_rate(),
+        }
+    }
+
+    /// Returns false if the decoder can not seek, intro loops never do since they record the loop while playing
+    fn try_seek(&mut self, position: Duration) -> bool {
+        match self {
+            Inner::File(decoder) => decoder.try_seek(position).is_ok(),
+            Inner::IntroLoop(decoder) => decoder.try_seek(position).is_ok(),
+        }
+    }
+}
+
+/// Seeks to the offset on the first read, skipping the interleaved samples before it if the decoder can not seek
+pub struct OffsetDecoder {
+    inner: Inner,
+    /// Set when the skipped part is played after the end
+    source: Option<AudioSource>,
+    channels: u16,
+    sample_rate: u32,
+    offset: Duration,
+    skip: u64,
+    skipped: bool,
+    /// Samples left of the skipped part once it is being played
+    remaining: Option<u64>,
+}
+
+impl Iterator for OffsetDecoder {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.skipped {
+            self.skipped = true;
+            // Skipping decodes everything before the offset on the audio thread, seeking avoids that
+            if self.inner.try_seek(self.offset) {
+                return self.next();
+            }
+            for _ in 0..self.skip {
+                if self.inner.next().is_none() {
+                    break;
+                }
+            }
+        }
+        if let Some(remaining) = self.remaining.as_mut() {
+            if *remaining == 0 {
+                return None;
+            }
+            *remaining -= 1;
+            return self.inner.next();
+        }
+        match self.inner.next() {
+            Some(sample) => Some(sample),
+            None => {
+                let source = self.source.take()?;
+                self.inner = Inner::File(source.decoder());
+                self.remaining = Some(self.skip);
+                self.next()
+            }
+        }
+    }
+}
+
+impl Source for OffsetDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Restarts the track of an entity at `position`
+pub(super) fn seek(entity: Entity, world: &mut World, position: Duration) {
+    let Some(settings) = world.get::<PlaybackSettings>(entity).copied() else {
+        warn!("Unable to seek {}, it is not playing audio", entity);
+        return;
+    };
+    if world.get::<AudioPlayer<SynthSource>>(entity).is_some() {
+        warn!(
+            "Unable to seek {}, synths can only be played from the start",
+            entity
+        );
+        return;
+    }
+    let source = if let Some(player) = world.get::<AudioPlayer<AudioSource>>(entity) {
+        world
+            .resource::<Assets<AudioSource>>()
+            .get(&player.0)
+            .map(|source| (source.clone(), None))
+    } else if let Some(player) = world.get::<AudioPlayer<IntroLoopSource>>(entity) {
+        world
+            .resource::<Assets<IntroLoopSource>>()
+            .get(&player.0)
+            .map(|source| (source.source.clone(), Some(source.loop_points)))
+    } else if let Some(player) = world.get::<AudioPlayer<OffsetSource>>(entity) {
+        world
+            .resource::<Assets<OffsetSource>>()
+            .get(&player.0)
+            .map(|source| (source.source.clone(), source.loop_points))
+    } else {
+        None
+    };
+    let Some((source, loop_points)) = source else {
+        warn!(
+            "Unable to seek {}, its audio source has not been loaded",
+            entity
+        );
+        return;
+    };
+
+    let handle = world
+        .resource_mut::<Assets<OffsetSource>>()
+        .add(OffsetSource::new(
+            source,
+            loop_points,
+            position,
+            settings.mode,
+        ));
+    // Keeping an audio player on the entity tells `remove_audio_components` that the track goes on
+    world
+        .entity_mut(entity)
+        .remove::<(
+            AudioPlayer<AudioSource>,
+            AudioPlayer<IntroLoopSource>,
+            AudioPlayer<OffsetSource>,
+            AudioPlayer<SynthSource>,
+            AudioSink,
+        )>()
+        .insert(AudioPlayer(handle));
+    let looping = matches!(settings.mode, PlaybackMode::Loop);
+    let id = world.get::<AudioFiles>(entity).copied().unwrap_or_default();
+    if let Some(mut progress) = world.get_mut::<PlaybackProgress>(entity) {
+        progress.set_loop_points(&id, loop_points);
+        progress.seek(position.as_secs_f32(), looping);
+    }
+}
+
+/// Returns the loop points that are used when the track is played with the intro loop enabled
+pub(super) fn get_loop_points(id: &AudioFiles) -> Option<LoopPoints> {
+    if matches!(id, AudioFiles::Unknown | AudioFiles::Dynamic(_)) {
+        None
+    } else {
+        id.get().loop_points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    /// A mono 16 bit WAV at 1 kHz whose samples count up from 0, so every sample is one millisecond
+    fn counting_source(len: i16) -> AudioSource {
+        let data_len = len as u32 * 2;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1000u32.to_le_bytes());
+        bytes.extend_from_slice(&2000u32.to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for sample in 0..len {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        AudioSource {
+            bytes: Arc::from(bytes),
+        }
+    }
+
+    fn play(
+        loop_points: Option<LoopPoints>,
+        offset_ms: u64,
+        mode: PlaybackMode,
+    ) -> impl Iterator<Item = Sample> {
+        OffsetSource::new(
+            counting_source(100),
+            loop_points,
+            Duration::from_millis(offset_ms),
+            mode,
+        )
+        .decoder()
+    }
+
+    #[test]
+    fn starts_at_the_offset() {
+        let samples: Vec<_> = play(None, 30, PlaybackMode::Once).collect();
+        assert_eq!(samples, (30..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn looping_plays_the_skipped_part_after_the_end() {
+        let samples: Vec<_> = play(None, 30, PlaybackMode::Loop).collect();
+        assert_eq!(samples, (30..100).chain(0..30).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn offset_past_the_end() {
+        assert_eq!(play(None, 250, PlaybackMode::Once).count(), 0);
+        // A looping track starts over, so the whole file is played once
+        let samples: Vec<_> = play(None, 250, PlaybackMode::Loop).collect();
+        assert_eq!(samples, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn intro_loops_skip_into_the_loop() {
+        let loop_points = LoopPoints { start: 20, end: 60 };
+        let samples: Vec<_> = play(Some(loop_points), 30, PlaybackMode::Once)
+            .take(50)
+            .collect();
+        assert_eq!(samples, (30..60).chain(20..40).collect::<Vec<_>>());
+        // Past the loop end the offset wraps into the loop
+        let samples: Vec<_> = play(Some(loop_points), 70, PlaybackMode::Once)
+            .take(5)
+            .collect();
+        assert_eq!(samples, (30..35).collect::<Vec<_>>());
+    }
+}
@@ -1,49 +1,163 @@
 use bevy::{
-    app::{App, Plugin, Startup, Update},
-    audio::AudioSink,
+    app::{App, Plugin, PostUpdate, Startup, Update},
+    asset::{AssetEvent, AssetLoadFailedEvent},
+    audio::{AddAudioSource, AudioSink, AudioSource},
     ecs::{
         component::Component,
         entity::Entity,
         query::Added,
-        system::{Commands, Query},
+        schedule::{
+            common_conditions::{on_event, resource_changed},
+            Condition, IntoSystemConfigs,
+        },
+        system::{Commands, Query, Res},
     },
     prelude::Without,
 };
 #[cfg(feature = "inspect")]
 use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
 
+#[cfg(feature = "inspect")]
+use crate::progress::PlaybackProgress;
+
 use crate::{
-    ac_assets::{load_assets, ACAssetLoader},
+    ac_assets::ACAssetLoader,
     audio_files::AudioFiles,
-    channel::ChannelRegistration,
+    channel::{remove_audio_components, ChannelRegistration},
+    dynamic::{register_loaded_folders, update_dynamic_durations, DynamicAudioRegistry},
+    dynamic_channel::{
+        dynamic_ecs_system, dynamic_play_event_reader, dynamic_settings_event_reader,
+        tick_dynamic_caches, update_dynamic_track_volumes, update_dynamic_volume_on_insert,
+        DynamicAudioCaches, DynamicChannel, DynamicChannels, DynamicPlayEvent,
+        DynamicSettingsEvent,
+    },
+    events::{AudioError, LoadAudioGroup, UnloadAudioGroup},
     global::GlobalChannel,
+    intro_loop::{IntroLoopCache, IntroLoopSource},
+    loading::{
+        load_all_assets, load_group_reader, report_failed_loads, unload_group_reader,
+        update_loading_progress, AudioLoadingProgress, LoadStrategy,
+    },
+    offset::OffsetSource,
+    progress::update_playback_progress,
+    registry::warn_unregistered_config_channels,
+    resources::ChannelSettings,
+    routing::{warn_unregistered_routes, AudioRouting},
+    synth::SynthSource,
 };
 
 /// Initializes the audio controller plugin
 /// - Registers the `GlobalChannel` as the default channel
-/// - Loads the audio assets
+/// - Loads the audio assets according to the [LoadStrategy]
 ///
 /// # Example
-/// ```
+/// ```no_run
+/// use bevy::prelude::*;
 /// use bevy_audio_controller::prelude::*;
 ///
 /// fn main() {
 ///     App::new()
-///         .add_plugins(AudioControllerPlugin)
+///         .add_plugins(AudioControllerPlugin::default().with_load_strategy(LoadStrategy::Lazy))
 ///         .run();
 /// }
 /// ```
-pub struct AudioControllerPlugin;
+#[derive(Default)]
+pub struct AudioControllerPlugin {
+    load_strategy: LoadStrategy,
+}
+
+impl AudioControllerPlugin {
+    /// Sets when the audio files are loaded, defaults to [LoadStrategy::Eager]
+    pub fn with_load_strategy(mut self, load_strategy: LoadStrategy) -> Self {
+        self.load_strategy = load_strategy;
+        self
+    }
+}
 
 impl Plugin for AudioControllerPlugin {
     fn build(&self, app: &mut App) {
+        if self.load_strategy == LoadStrategy::Eager {
+            app.add_systems(Startup, load_all_assets);
+        }
+        app.add_systems(
+            Startup,
+            (warn_unregistered_config_channels, warn_unregistered_routes),
+        );
+
         app.init_resource::<ACAssetLoader>()
+            .init_resource::<AudioRouting>()
+            .init_resource::<DynamicChannels>()
+            .init_resource::<DynamicAudioCaches>()
+            .add_event::<DynamicPlayEvent>()
+            .add_event::<DynamicSettingsEvent>()
+            .init_resource::<AudioLoadingProgress>()
+            .insert_resource(self.load_strategy)
+            .add_event::<LoadAudioGroup>()
+            .add_event::<UnloadAudioGroup>()
+            .add_event::<AudioError>()
+            .init_resource::<IntroLoopCache>()
+            .init_resource::<DynamicAudioRegistry>()
+            .add_audio_source::<IntroLoopSource>()
+            .add_audio_source::<OffsetSource>()
+            .add_audio_source::<SynthSource>()
             .register_audio_channel::<GlobalChannel>()
-            .add_systems(Startup, load_assets)
-            .add_systems(Update, (assign_to_global_on_sink, assign_to_global_on_file));
+            .add_systems(
+                Update,
+                (
+                    (
+                        load_group_reader.run_if(on_event::<LoadAudioGroup>),
+                        unload_group_reader.run_if(on_event::<UnloadAudioGroup>),
+                        update_loading_progress.run_if(
+                            resource_changed::<ACAssetLoader>
+                                .or(on_event::<AssetEvent<AudioSource>>)
+                                .or(on_event::<AssetLoadFailedEvent<AudioSource>>),
+                        ),
+                    )
+                        .chain(),
+                    assign_to_global_on_sink,
+                    route_on_file,
+                    tick_dynamic_caches,
+                    dynamic_ecs_system,
+                    update_dynamic_volume_on_insert,
+                    (
+                        dynamic_settings_event_reader.run_if(on_event::<DynamicSettingsEvent>),
+                        update_dynamic_track_volumes.run_if(
+                            resource_changed::<DynamicChannels>
+                                .or(resource_changed::<ChannelSettings<GlobalChannel>>),
+                        ),
+                    )
+                        .chain(),
+                    register_loaded_folders,
+                    update_dynamic_durations.run_if(
+                        on_event::<AssetEvent<AudioSource>>
+                            .or(|registry: Res<DynamicAudioRegistry>| registry.is_measuring()),
+                    ),
+                    report_failed_loads.run_if(on_event::<AssetLoadFailedEvent<AudioSource>>),
+                    update_playback_progress,
+                ),
+            );
+
+        app.add_systems(
+            PostUpdate,
+            (
+                remove_audio_components::<DynamicChannel>,
+                dynamic_play_event_reader.run_if(on_event::<DynamicPlayEvent>),
+            ),
+        );
+        app.world_mut()
+            .register_component_hooks::<DynamicChannel>()
+            .on_add(|mut world, entity, _| {
+                world.commands().entity(entity).insert(HasChannel);
+            });
 
         #[cfg(feature = "inspect")]
-        app.register_type::<ACAssetLoader>();
+        app.register_type::<ACAssetLoader>()
+            .register_type::<DynamicChannel>()
+            .register_type::<DynamicChannels>()
+            .register_type::<DynamicAudioRegistry>()
+            .register_type::<LoadStrategy>()
+            .register_type::<AudioLoadingProgress>()
+            .register_type::<PlaybackProgress>();
     }
 }
 
@@ -65,13 +179,32 @@ fn assign_to_global_on_sink(
     }
 }
 
-fn assign_to_global_on_file(
+#[allow(clippy::type_complexity)]
+fn route_on_file(
     mut commands: Commands,
-    query: Query<(Entity, Option<&HasChannel>), (Added<AudioFiles>, Without<AudioSink>)>,
+    routing: Res<AudioRouting>,
+    dynamic_registry: Res<DynamicAudioRegistry>,
+    query: Query<
+        (Entity, &AudioFiles, Option<&HasChannel>),
+        (Added<AudioFiles>, Without<AudioSink>),
+    >,
 ) {
-    for (entity, has_channel_opt) in query.iter() {
-        if has_channel_opt.is_none() {
-            commands.entity(entity).insert(GlobalChannel);
+    for (entity, id, has_channel_opt) in query.iter() {
+        if has_channel_opt.is_some() {
+            continue;
+        }
+        let path = if let AudioFiles::Dynamic(_) = id {
+            dynamic_registry.get_path(id)
+        } else if *id != AudioFiles::Unknown {
+            Some(id.path())
+        } else {
+            None
+        };
+        let mut entity_commands = commands.entity(entity);
+        if let Some(insert_channel) = routing.get(id, path) {
+            insert_channel(&mut entity_commands);
+        } else {
+            entity_commands.insert(GlobalChannel);
         }
     }
 }
@@ -0,0 +1,194 @@
+use bevy::{
+    audio::{AudioSink, AudioSinkPlayback, PlaybackMode, PlaybackSettings},
+    ecs::{
+        component::Component,
+        system::{Query, Res},
+    },
+    time::Time,
+};
+#[cfg(feature = "inspect")]
+use bevy::{ecs::reflect::ReflectComponent, reflect::Reflect};
+
+use crate::{
+    audio_files::{AudioFiles, LoopPoints},
+    dynamic::DynamicAudioRegistry,
+    helpers,
+};
+
+/// The playback position of a track, inserted on every entity the controller plays audio on
+///
+/// Advances every frame at the speed of the [AudioSink] and stands still while it is paused or has not started yet.
+/// Looping tracks start over at 0.0, tracks with an intro loop go back to the start of their [LoopPoints], and both count their loops
+///
+/// # Example
+/// ```ignore
+/// fn music_ui(query: Query<&PlaybackProgress, With<MusicChannel>>, mut bar: Single<&mut Node, With<ProgressBar>>) {
+///     if let Some(progress) = query.iter().next() {
+///         bar.width = Val::Percent(progress.fraction() * 100.0);
+///     }
+/// }
+/// ```
+#[derive(Component, Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+#[cfg_attr(feature = "inspect", reflect(Component))]
+pub struct PlaybackProgress {
+    elapsed: f32,
+    duration: f32,
+    loops: u32,
+    /// Start and end of the loop in seconds, for tracks that play their intro once and then loop
+    loop_region: Option<(f32, f32)>,
+}
+
+impl PlaybackProgress {
+    pub(super) fn new(duration: f32) -> Self {
+        Self {
+            duration,
+            ..Default::default()
+        }
+    }
+
+    /// Makes the position wrap from the loop end back to the loop start, used when the track is played with its intro loop
+    pub(super) fn set_loop_points(&mut self, id: &AudioFiles, loop_points: Option<LoopPoints>) {
+        self.loop_region = loop_points.and_then(|loop_points| {
+            let sample_rate = id.get().sample_rate as f32;
+            let start = loop_points.start as f32 / sample_rate;
+            let end = loop_points.end as f32 / sample_rate;
+            (sample_rate > 0.0 && end > start).then_some((start, end))
+        });
+    }
+
+    /// Moves the position to `position` seconds, wrapping it into the track when it loops
+    pub(super) fn seek(&mut self, position: f32, looping: bool) {
+        self.elapsed = position;
+        self.wrap(looping);
+    }
+
+    /// Wraps the position back into the track and returns how many loops were completed on the way
+    fn wrap(&mut self, looping: bool) -> u32 {
+        if let Some((start, end)) = self.loop_region {
+            if self.elapsed < end {
+                return 0;
+            }
+            let length = end - start;
+            let looped = self.elapsed - start;
+            self.elapsed = start + looped % length;
+            (looped / length) as u32
+        } else if self.duration <= 0.0 || self.elapsed < self.duration {
+            0
+        } else if looping {
+            let loops = self.elapsed / self.duration;
+            self.elapsed %= self.duration;
+            loops as u32
+        } else {
+            self.elapsed = self.duration;
+            0
+        }
+    }
+
+    /// Returns the seconds of the track that have been played, within the current loop
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Returns the seconds of the track that are left, within the current loop
+    ///
+    /// Tracks with an intro loop measure it up to the loop end
+    pub fn remaining(&self) -> f32 {
+        let end = self.loop_region.map_or(self.duration, |(_, end)| end);
+        (end - self.elapsed).max(0.0)
+    }
+
+    /// Returns the duration of the track in seconds, 0.0 if it is not known yet
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Returns how much of the track has been played on a scale of 0.0 - 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.duration > 0.0 {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns how many times a looping track has started over, or jumped back to the start of its intro loop
+    pub fn loops(&self) -> u32 {
+        self.loops
+    }
+}
+
+pub(super) fn update_playback_progress(
+    time: Res<Time>,
+    dynamic_registry: Res<DynamicAudioRegistry>,
+    mut query: Query<(
+        &mut PlaybackProgress,
+        &AudioFiles,
+        &AudioSink,
+        Option<&PlaybackSettings>,
+    )>,
+) {
+    for (mut progress, id, sink, settings) in query.iter_mut() {
+        if sink.is_paused() {
+            continue;
+        }
+        if progress.duration <= 0.0 {
+            // Dynamic files only know their duration once they are loaded
+            progress.duration = helpers::get_track_duration(id, &dynamic_registry);
+        }
+        progress.elapsed += time.delta_secs() * sink.speed();
+        let looping = settings.is_some_and(|settings| matches!(settings.mode, PlaybackMode::Loop));
+        let loops = progress.wrap(looping);
+        progress.loops += loops;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looping_counts_every_loop() {
+        let mut progress = PlaybackProgress::new(2.0);
+        progress.elapsed = 5.0;
+        assert_eq!(progress.wrap(true), 2);
+        assert_eq!(progress.elapsed, 1.0);
+        progress.elapsed = 1.5;
+        assert_eq!(progress.wrap(true), 0);
+    }
+
+    #[test]
+    fn once_stops_at_the_end() {
+        let mut progress = PlaybackProgress::new(2.0);
+        progress.elapsed = 5.0;
+        assert_eq!(progress.wrap(false), 0);
+        assert_eq!(progress.elapsed, 2.0);
+    }
+
+    #[test]
+    fn intro_loop_wraps_to_the_loop_start() {
+        let mut progress = PlaybackProgress::new(10.0);
+        progress.loop_region = Some((2.0, 6.0));
+        progress.elapsed = 7.0;
+        assert_eq!(progress.wrap(false), 1);
+        assert_eq!(progress.elapsed, 3.0);
+        progress.elapsed = 14.0;
+        assert_eq!(progress.wrap(false), 3);
+        assert_eq!(progress.elapsed, 2.0);
+        progress.seek(1.0, false);
+        assert_eq!(progress.elapsed, 1.0);
+        progress.seek(9.0, false);
+        assert_eq!(progress.elapsed, 5.0);
+    }
+
+    #[test]
+    fn intro_loop_remaining_ends_at_the_loop_end() {
+        let mut progress = PlaybackProgress::new(10.0);
+        progress.loop_region = Some((2.0, 6.0));
+        progress.elapsed = 1.0;
+        assert_eq!(progress.remaining(), 5.0);
+        progress.elapsed = 7.0;
+        assert_eq!(progress.wrap(false), 1);
+        assert_eq!(progress.remaining(), 3.0);
+    }
+}
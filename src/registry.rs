@@ -0,0 +1,315 @@
+use bevy::{
+    audio::PlaybackSettings,
+    ecs::{
+        system::{Res, Resource},
+        world::World,
+    },
+    log::warn,
+    utils::hashbrown::HashSet,
+};
+
+use crate::{
+    audio_config::TRACK_CONFIGS,
+    bounds::ACBounds,
+    delay_mode::DelayMode,
+    dynamic_channel::{short_name, DynamicChannel},
+    resources::ChannelSettings,
+};
+
+type SettingsMut = fn(&mut World, &mut dyn FnMut(&mut dyn ErasedSettings));
+
+/// Every channel that was registered with [crate::channel::ChannelRegistration::register_audio_channel],
+/// in registration order with the [crate::global::GlobalChannel] first
+///
+/// Lets UI or persistence code go over all channels without knowing their types
+///
+/// # Example
+/// ```ignore
+/// fn save_volumes(world: &mut World) {
+///     let registry = world.resource::<AudioChannelRegistry>().clone();
+///     for channel in registry.iter() {
+///         info!("{}: {}", channel.display_name(), channel.volume(world));
+///     }
+/// }
+/// ```
+#[derive(Resource, Default, Clone)]
+pub struct AudioChannelRegistry {
+    channels: Vec<ChannelInfo>,
+}
+
+impl AudioChannelRegistry {
+    /// Returns every registered channel
+    pub fn iter(&self) -> impl Iterator<Item = &ChannelInfo> {
+        self.channels.iter()
+    }
+
+    /// Returns the number of registered channels
+    pub fn len(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Returns true if no channel was registered yet
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    /// Returns the info of a typed channel
+    pub fn get<Channel: ACBounds>(&self) -> Option<&ChannelInfo> {
+        let type_name = std::any::type_name::<Channel>();
+        self.channels
+            .iter()
+            .find(|info| info.type_name == type_name)
+    }
+
+    /// Returns the channel with the given display name, full type name or short type name
+    pub fn get_by_name(&self, name: &str) -> Option<&ChannelInfo> {
+        self.channels.iter().find(|info| {
+            info.display_name == name
+                || info.type_name == name
+                || short_name(info.type_name) == name
+        })
+    }
+
+    /// Sets the name that is shown for a channel, defaults to the type name without the `Channel` suffix
+    pub fn set_display_name<Channel: ACBounds>(&mut self, name: impl Into<String>) {
+        let type_name = std::any::type_name::<Channel>();
+        if let Some(info) = self
+            .channels
+            .iter_mut()
+            .find(|info| info.type_name == type_name)
+        {
+            info.display_name = name.into();
+        }
+    }
+
+    /// Returns true if a channel with the full or short type name was registered
+    pub(super) fn contains_type_name(&self, name: &str) -> bool {
+        self.channels
+            .iter()
+            .any(|info| info.type_name == name || short_name(info.type_name) == name)
+    }
+
+    /// Returns false without changing anything if the parent is the channel itself or one of its children
+    pub(super) fn set_parent<Channel: ACBounds, Parent: ACBounds>(&mut self) -> bool {
+        let type_name = std::any::type_name::<Channel>();
+        let mut ancestor = Some(std::any::type_name::<Parent>());
+        while let Some(current) = ancestor {
+            if current == type_name {
+                return false;
+            }
+            ancestor = self
+                .channels
+                .iter()
+                .find(|info| info.type_name == current)
+                .and_then(|info| info.parent);
+        }
+        if let Some(info) = self
+            .channels
+            .iter_mut()
+            .find(|info| info.type_name == type_name)
+        {
+            info.parent = Some(std::any::type_name::<Parent>());
+        }
+        true
+    }
+
+    pub(super) fn register<Channel: ACBounds>(&mut self) {
+        if self.get::<Channel>().is_some() {
+            return;
+        }
+        self.channels.push(ChannelInfo::new::<Channel>());
+    }
+}
+
+/// The defaults of config entries that name a channel which was never registered end up nowhere, warn about them once
+pub(super) fn warn_unregistered_config_channels(registry: Res<AudioChannelRegistry>) {
+    let mut warned = HashSet::new();
+    for config in TRACK_CONFIGS {
+        let Some(channel) = config.channel else {
+            continue;
+        };
+        if !registry.contains_type_name(channel) && warned.insert(channel) {
+            warn!(
+                "The audio config names the {} channel, which is not registered, the defaults of its files are ignored",
+                channel
+            );
+        }
+    }
+}
+
+/// A registered channel with type erased access to its [ChannelSettings]
+#[derive(Clone)]
+pub struct ChannelInfo {
+    type_name: &'static str,
+    display_name: String,
+    channel: DynamicChannel,
+    parent: Option<&'static str>,
+    settings: fn(&World) -> &dyn ErasedSettings,
+    settings_mut: SettingsMut,
+}
+
+impl ChannelInfo {
+    fn new<Channel: ACBounds>() -> Self {
+        let type_name = std::any::type_name::<Channel>();
+        let short = short_name(type_name);
+        let display_name = match short.strip_suffix("Channel") {
+            Some(name) if !name.is_empty() => name,
+            _ => short,
+        };
+        Self {
+            type_name,
+            display_name: display_name.to_string(),
+            channel: DynamicChannel::new(short),
+            parent: None,
+            settings: |world| world.resource::<ChannelSettings<Channel>>(),
+            settings_mut: |world, f| f(&mut *world.resource_mut::<ChannelSettings<Channel>>()),
+        }
+    }
+
+    /// Returns the full type name of the channel
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the name to show for the channel
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    /// Returns the [DynamicChannel] the channel can be addressed by
+    pub fn dynamic_channel(&self) -> DynamicChannel {
+        self.channel
+    }
+
+    /// Returns the type name of the parent channel, see [crate::channel::ChannelRegistration::set_audio_channel_parent]
+    pub fn parent(&self) -> Option<&'static str> {
+        self.parent
+    }
+
+    /// Returns the volume of the channel
+    pub fn volume(&self, world: &World) -> f32 {
+        (self.settings)(world).volume()
+    }
+
+    /// Sets the volume of the channel
+    pub fn set_volume(&self, world: &mut World, volume: f32) {
+        (self.settings_mut)(world, &mut |settings| settings.set_volume(volume));
+    }
+
+    /// Returns whether the channel is muted
+    pub fn is_muted(&self, world: &World) -> bool {
+        (self.settings)(world).is_muted()
+    }
+
+    /// Mutes or unmutes the channel
+    pub fn set_muted(&self, world: &mut World, muted: bool) {
+        (self.settings_mut)(world, &mut |settings| settings.set_muted(muted));
+    }
+
+    /// Returns the default [PlaybackSettings] of the channel
+    pub fn default_settings(&self, world: &World) -> PlaybackSettings {
+        (self.settings)(world).default_settings()
+    }
+
+    /// Sets the default [PlaybackSettings] of the channel
+    pub fn set_default_settings(&self, world: &mut World, settings: PlaybackSettings) {
+        (self.settings_mut)(world, &mut |erased| erased.set_default_settings(settings));
+    }
+
+    /// Returns the default [DelayMode] of the channel
+    pub fn default_delay_mode(&self, world: &World) -> DelayMode {
+        (self.settings)(world).default_delay_mode()
+    }
+
+    /// Sets the default [DelayMode] of the channel
+    pub fn set_default_delay_mode(&self, world: &mut World, delay_mode: DelayMode) {
+        (self.settings_mut)(world, &mut |settings| {
+            settings.set_default_delay_mode(delay_mode)
+        });
+    }
+}
+
+trait ErasedSettings {
+    fn volume(&self) -> f32;
+    fn set_volume(&mut self, volume: f32);
+    fn is_muted(&self) -> bool;
+    fn set_muted(&mut self, muted: bool);
+    fn default_settings(&self) -> PlaybackSettings;
+    fn set_default_settings(&mut self, settings: PlaybackSettings);
+    fn default_delay_mode(&self) -> DelayMode;
+    fn set_default_delay_mode(&mut self, delay_mode: DelayMode);
+}
+
+impl<Channel: ACBounds> ErasedSettings for ChannelSettings<Channel> {
+    fn volume(&self) -> f32 {
+        self.get_channel_volume()
+    }
+
+    fn set_volume(&mut self, volume: f32) {
+        self.set_channel_volume(volume);
+    }
+
+    fn is_muted(&self) -> bool {
+        ChannelSettings::is_muted(self)
+    }
+
+    fn set_muted(&mut self, muted: bool) {
+        ChannelSettings::set_muted(self, muted);
+    }
+
+    fn default_settings(&self) -> PlaybackSettings {
+        self.get_default_settings()
+    }
+
+    fn set_default_settings(&mut self, settings: PlaybackSettings) {
+        ChannelSettings::set_default_settings(self, settings);
+    }
+
+    fn default_delay_mode(&self) -> DelayMode {
+        self.get_default_delay_mode()
+    }
+
+    fn set_default_delay_mode(&mut self, delay_mode: DelayMode) {
+        ChannelSettings::set_default_delay_mode(self, delay_mode);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::component::Component;
+    #[cfg(feature = "inspect")]
+    use bevy::reflect::Reflect;
+
+    use super::*;
+
+    #[derive(Component, Default)]
+    #[cfg_attr(feature = "inspect", derive(Reflect))]
+    struct MasterChannel;
+
+    #[derive(Component, Default)]
+    #[cfg_attr(feature = "inspect", derive(Reflect))]
+    struct SfxChannel;
+
+    #[derive(Component, Default)]
+    #[cfg_attr(feature = "inspect", derive(Reflect))]
+    struct FootstepsChannel;
+
+    #[test]
+    fn parent_cycles_are_rejected() {
+        let mut registry = AudioChannelRegistry::default();
+        registry.register::<MasterChannel>();
+        registry.register::<SfxChannel>();
+        registry.register::<FootstepsChannel>();
+
+        assert!(registry.set_parent::<SfxChannel, MasterChannel>());
+        assert!(registry.set_parent::<FootstepsChannel, SfxChannel>());
+        assert!(!registry.set_parent::<MasterChannel, FootstepsChannel>());
+        assert!(!registry.set_parent::<MasterChannel, MasterChannel>());
+
+        assert_eq!(registry.get::<MasterChannel>().unwrap().parent(), None);
+        assert_eq!(
+            registry.get::<FootstepsChannel>().unwrap().parent(),
+            Some(std::any::type_name::<SfxChannel>())
+        );
+    }
+}
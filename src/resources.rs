@@ -1,7 +1,7 @@
 use std::marker::PhantomData;
 
 use bevy::{
-    audio::{PlaybackSettings, Volume},
+    audio::{PlaybackMode, PlaybackSettings, Volume},
     ecs::system::Resource,
     time::{Timer, TimerMode},
     utils::hashbrown::HashMap,
@@ -9,7 +9,13 @@ use bevy::{
 #[cfg(feature = "inspect")]
 use bevy::{ecs::reflect::ReflectResource, reflect::Reflect};
 
-use crate::{bounds::ACBounds, prelude::DelayMode};
+use crate::{
+    audio_config::{TrackConfig, TRACK_CONFIGS},
+    bounds::ACBounds,
+    events::SettingsEvent,
+    global::GlobalChannel,
+    prelude::DelayMode,
+};
 
 use super::audio_files::{AudioFiles, ALL_FILES};
 
@@ -18,20 +24,134 @@ use super::audio_files::{AudioFiles, ALL_FILES};
 /// It is recommended to mutate this resource via the [crate::events::SettingsEvent] event
 ///
 /// Compared to calling the resource directly, but either is supported
-#[derive(Default, Resource)]
+///
+/// Starts out with the track settings from the `audio.toml` or `audio.ron` file in your assets folder
+#[derive(Resource)]
 #[cfg_attr(feature = "inspect", derive(Reflect))]
 #[cfg_attr(feature = "inspect", reflect(Resource))]
 pub struct ChannelSettings<Channel: ACBounds> {
     channel_volume: Volume,
+    muted: bool,
+    parent_volume: f32,
     track_settings: HashMap<AudioFiles, PlaybackSettings>,
     track_delay_modes: HashMap<AudioFiles, DelayMode>,
+    track_intro_loops: HashMap<AudioFiles, bool>,
     default_settings: PlaybackSettings,
     default_delay_mode: DelayMode,
+    default_intro_loop: bool,
+    normalization_target: Option<f32>,
+    fallback: Option<AudioFiles>,
     #[cfg_attr(feature = "inspect", reflect(ignore))]
     _marker: PhantomData<Channel>,
 }
 
+impl<T: ACBounds> Default for ChannelSettings<T> {
+    fn default() -> Self {
+        Self::from_config(Self::is_channel)
+    }
+}
+
 impl<T: ACBounds> ChannelSettings<T> {
+    /// Starts out with the track settings of the config entries that match `filter`
+    pub(super) fn from_config(filter: impl Fn(&TrackConfig) -> bool) -> Self {
+        let mut settings = Self {
+            channel_volume: Volume::default(),
+            muted: false,
+            parent_volume: 1.0,
+            track_settings: HashMap::default(),
+            track_delay_modes: HashMap::default(),
+            track_intro_loops: HashMap::default(),
+            default_settings: PlaybackSettings::default(),
+            default_delay_mode: DelayMode::default(),
+            default_intro_loop: false,
+            normalization_target: None,
+            fallback: None,
+            _marker: PhantomData,
+        };
+        for config in TRACK_CONFIGS.iter().filter(|config| filter(config)) {
+            settings.apply_config(config);
+        }
+        settings
+    }
+
+    /// Config entries name the channel by its type name, with or without the module path
+    pub(super) fn is_channel(config: &TrackConfig) -> bool {
+        let type_name = std::any::type_name::<T>();
+        match config.channel {
+            Some(channel) => type_name == channel || type_name.rsplit("::").next() == Some(channel),
+            None => type_name == std::any::type_name::<GlobalChannel>(),
+        }
+    }
+
+    pub(super) fn apply_event(&mut self, event: &SettingsEvent<T>) {
+        if let Some(volume) = event.volume {
+            self.set_channel_volume(volume);
+        }
+        if let Some(muted) = event.muted {
+            self.set_muted(muted);
+        }
+        if let Some(target) = event.normalization_target {
+            self.set_normalization_target(target);
+        }
+        if let Some(fallback) = event.fallback {
+            self.set_fallback(fallback);
+        }
+        if let Some(id) = event.track {
+            if let Some(delay_mode) = event.delay_mode {
+                self.set_track_delay_mode(id, delay_mode);
+            }
+            if let Some(intro_loop) = event.intro_loop {
+                self.set_track_intro_loop(id, intro_loop);
+            }
+            if let Some(settings) = event.settings {
+                self.set_track_settings(id, settings);
+            }
+        } else if event.all {
+            if let Some(delay_mode) = event.delay_mode {
+                self.set_all_track_delay_modes(delay_mode);
+            }
+            if let Some(intro_loop) = event.intro_loop {
+                self.set_all_track_intro_loops(intro_loop);
+            }
+            if let Some(settings) = event.settings {
+                self.set_all_track_settings(settings);
+            }
+        } else {
+            if let Some(delay_mode) = event.delay_mode {
+                self.set_default_delay_mode(delay_mode);
+            }
+            if let Some(intro_loop) = event.intro_loop {
+                self.set_default_intro_loop(intro_loop);
+            }
+            if let Some(settings) = event.settings {
+                self.set_default_settings(settings);
+            }
+        }
+    }
+
+    fn apply_config(&mut self, config: &TrackConfig) {
+        if config.volume.is_some() || config.speed.is_some() || config.looping.is_some() {
+            let mut settings = self.get_track_setting(&config.id);
+            if let Some(volume) = config.volume {
+                settings.volume = Volume::new(volume);
+            }
+            if let Some(speed) = config.speed {
+                settings.speed = speed;
+            }
+            match config.looping {
+                Some(true) => settings.mode = PlaybackMode::Loop,
+                Some(false) if matches!(settings.mode, PlaybackMode::Loop) => {
+                    settings.mode = PlaybackMode::Once
+                }
+                _ => {}
+            }
+            self.set_track_settings(config.id, settings);
+        }
+        if let Some(delay_mode) = config.delay_mode {
+            self.set_track_delay_mode(config.id, delay_mode);
+        }
+    }
+
     /// Returns the volume of the channel on a scale of 0.0 - 1.0
     pub fn get_channel_volume(&self) -> f32 {
         self.channel_volume.get()
@@ -42,13 +162,40 @@ impl<T: ACBounds> ChannelSettings<T> {
         self.channel_volume = Volume::new(volume);
     }
 
+    /// Returns whether the channel is muted, muting keeps the volume so unmuting restores it
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    /// Mutes or unmutes the channel
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
+    /// Returns the volume tracks of the channel end up with before the [GlobalChannel] is applied,
+    /// taking muting and parent channels into account
+    pub fn get_effective_volume(&self) -> f32 {
+        if self.muted {
+            return 0.0;
+        }
+        self.get_channel_volume() * self.parent_volume
+    }
+
+    pub(super) fn get_parent_volume(&self) -> f32 {
+        self.parent_volume
+    }
+
+    pub(super) fn set_parent_volume(&mut self, volume: f32) {
+        self.parent_volume = volume;
+    }
+
     /// Returns the [PlaybackSettings] for a specific track
     ///
     /// or the default settings if the track does not have any settings for this channel
     pub fn get_track_setting(&self, id: &AudioFiles) -> PlaybackSettings {
         self.track_settings
             .get(id)
-            .map_or(self.default_settings, |settings| settings.clone())
+            .map_or(self.default_settings, |settings| *settings)
     }
 
     /// Sets the [PlaybackSettings] for a specific track
@@ -59,7 +206,7 @@ impl<T: ACBounds> ChannelSettings<T> {
     /// Sets the [PlaybackSettings] for all tracks in this channel that exist in your asset folder
     pub fn set_all_track_settings(&mut self, settings: PlaybackSettings) {
         for track in ALL_FILES {
-            self.track_settings.insert(track, settings.clone());
+            self.track_settings.insert(track, settings);
         }
     }
 
@@ -67,7 +214,7 @@ impl<T: ACBounds> ChannelSettings<T> {
     pub fn get_track_delay_mode(&self, id: &AudioFiles) -> DelayMode {
         self.track_delay_modes
             .get(id)
-            .map_or(self.default_delay_mode, |mode| mode.clone())
+            .map_or(self.default_delay_mode, |mode| *mode)
     }
 
     /// Sets the [DelayMode] for a specific track
@@ -78,13 +225,44 @@ impl<T: ACBounds> ChannelSettings<T> {
     /// Sets the [DelayMode] for all tracks in this channel that exist in your asset folder
     pub fn set_all_track_delay_modes(&mut self, delay_mode: DelayMode) {
         for track in ALL_FILES {
-            self.track_delay_modes.insert(track, delay_mode.clone());
+            self.track_delay_modes.insert(track, delay_mode);
         }
     }
 
+    /// Returns whether a specific track plays its intro once and then loops between its [LoopPoints](crate::audio_files::LoopPoints)
+    ///
+    /// or the channel default if the track does not have a value for this channel
+    pub fn get_track_intro_loop(&self, id: &AudioFiles) -> bool {
+        self.track_intro_loops
+            .get(id)
+            .map_or(self.default_intro_loop, |intro_loop| *intro_loop)
+    }
+
+    /// Sets whether a specific track plays its intro once and then loops between its [LoopPoints](crate::audio_files::LoopPoints)
+    pub fn set_track_intro_loop(&mut self, id: AudioFiles, intro_loop: bool) {
+        self.track_intro_loops.insert(id, intro_loop);
+    }
+
+    /// Sets whether all tracks in this channel that exist in your asset folder play their intro once and then loop
+    pub fn set_all_track_intro_loops(&mut self, intro_loop: bool) {
+        for track in ALL_FILES {
+            self.track_intro_loops.insert(track, intro_loop);
+        }
+    }
+
+    /// Returns whether tracks in this channel play their intro once and then loop by default
+    pub fn get_default_intro_loop(&self) -> bool {
+        self.default_intro_loop
+    }
+
+    /// Sets whether tracks in this channel play their intro once and then loop by default
+    pub fn set_default_intro_loop(&mut self, intro_loop: bool) {
+        self.default_intro_loop = intro_loop;
+    }
+
     /// Returns the default [PlaybackSettings] for this channel
     pub fn get_default_settings(&self) -> PlaybackSettings {
-        self.default_settings.clone()
+        self.default_settings
     }
 
     /// Sets the default [PlaybackSettings] for this channel
@@ -101,6 +279,52 @@ impl<T: ACBounds> ChannelSettings<T> {
     pub fn set_default_delay_mode(&mut self, delay_mode: DelayMode) {
         self.default_delay_mode = delay_mode;
     }
+
+    /// Returns the loudness target in LUFS that tracks in this channel are normalized to, if any
+    pub fn get_normalization_target(&self) -> Option<f32> {
+        self.normalization_target
+    }
+
+    /// Sets the loudness target in LUFS that tracks in this channel are normalized to, e.g. -16.0
+    ///
+    /// `None` disables normalization, which is the default. Tracks that are already playing follow the new target
+    pub fn set_normalization_target(&mut self, target: Option<f32>) {
+        self.normalization_target = target;
+    }
+
+    /// Returns the gain trim (as a volume multiplier) that brings a track to the normalization target
+    ///
+    /// The trim is capped so the track's true peak does not exceed 0 dBTP,
+    /// and is 1.0 when normalization is disabled or the track has no loudness data
+    pub fn get_track_gain(&self, id: &AudioFiles) -> f32 {
+        let Some(target) = self.normalization_target else {
+            return 1.0;
+        };
+        if matches!(id, AudioFiles::Unknown | AudioFiles::Dynamic(_)) {
+            return 1.0;
+        }
+        let file = id.get();
+        let Some(loudness) = file.loudness else {
+            return 1.0;
+        };
+        let mut gain_db = target - loudness;
+        if let Some(true_peak) = file.true_peak {
+            gain_db = gain_db.min(-true_peak);
+        }
+        10f32.powf(gain_db / 20.0)
+    }
+
+    /// Returns the file that is played instead when a requested file can not be played, if any
+    pub fn get_fallback(&self) -> Option<AudioFiles> {
+        self.fallback
+    }
+
+    /// Sets the file that is played instead when a requested file can not be played
+    ///
+    /// `None` disables the fallback, which is the default
+    pub fn set_fallback(&mut self, fallback: Option<AudioFiles>) {
+        self.fallback = fallback;
+    }
 }
 
 #[derive(Default, Resource)]
@@ -120,11 +344,26 @@ impl<T: ACBounds> AudioCache<T> {
     }
 
     pub(super) fn can_play(&self, id: &AudioFiles) -> bool {
-        self.map.get(id).map_or(true, |timer| timer.finished())
+        self.map.get(id).is_none_or(|timer| timer.finished())
     }
 
     pub(super) fn set_entry(&mut self, id: AudioFiles, duration: f32) {
         self.map
             .insert(id, Timer::from_seconds(duration, TimerMode::Once));
     }
+
+    /// Returns whether the track may play now and starts its delay if it does,
+    /// `duration` is the playback duration in seconds at the speed it is played at
+    pub(super) fn try_play(
+        &mut self,
+        id: AudioFiles,
+        delay_mode: DelayMode,
+        duration: f32,
+    ) -> bool {
+        let can_play = self.can_play(&id);
+        if can_play {
+            self.set_entry(id, delay_mode.get_delay(duration));
+        }
+        can_play || delay_mode == DelayMode::Immediate
+    }
 }
@@ -0,0 +1,114 @@
+use bevy::{
+    ecs::system::{EntityCommands, Res, Resource},
+    log::warn,
+    utils::hashbrown::HashMap,
+};
+
+use crate::{audio_files::AudioFiles, bounds::ACBounds, registry::AudioChannelRegistry};
+
+type InsertChannel = for<'a> fn(&mut EntityCommands<'a>);
+
+/// The channel a file or folder is routed to
+#[derive(Clone, Copy)]
+struct Route {
+    /// Full type name of the channel
+    channel: &'static str,
+    insert: InsertChannel,
+}
+
+impl Route {
+    fn new<Channel: ACBounds>() -> Self {
+        Self {
+            channel: std::any::type_name::<Channel>(),
+            insert: insert_channel::<Channel>,
+        }
+    }
+}
+
+/// Decides which channel an [AudioFiles] entity that was spawned without one ends up in
+///
+/// Files are routed by the channel named in the `audio.toml` or `audio.ron` file in your assets folder first,
+/// then by the longest matching folder from [crate::channel::ChannelRegistration::route_audio_folder],
+/// and anything else goes to the [crate::global::GlobalChannel]
+#[derive(Default, Resource)]
+pub(super) struct AudioRouting {
+    files: HashMap<AudioFiles, Route>,
+    folders: Vec<(String, Route)>,
+}
+
+impl AudioRouting {
+    /// The first channel keeps the file, config entries that match more than one channel have to use the full type name
+    pub(super) fn add_file<Channel: ACBounds>(&mut self, id: AudioFiles) {
+        let route = Route::new::<Channel>();
+        match self.files.get(&id) {
+            Some(existing) if existing.channel != route.channel => warn!(
+                "The audio config channel of {} matches both {} and {}, use the full type name to pick one",
+                id, existing.channel, route.channel
+            ),
+            Some(_) => {}
+            None => {
+                self.files.insert(id, route);
+            }
+        }
+    }
+
+    pub(super) fn add_folder<Channel: ACBounds>(&mut self, folder: &str) {
+        let folder = folder
+            .replace('\\', "/")
+            .trim_end_matches("**")
+            .trim_matches('/')
+            .to_string();
+        let route = Route::new::<Channel>();
+        if let Some((_, existing)) = self
+            .folders
+            .iter()
+            .find(|(existing, _)| *existing == folder)
+        {
+            if existing.channel != route.channel {
+                warn!(
+                    "The {} folder was routed to {}, it is routed to {} now",
+                    folder, existing.channel, route.channel
+                );
+            }
+        }
+        self.folders.retain(|(existing, _)| *existing != folder);
+        self.folders.push((folder, route));
+        // Longest folder first, so `music/boss` wins over `music`
+        self.folders
+            .sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then(a.cmp(b)));
+    }
+
+    /// Returns the function that inserts the channel component for the file, `None` if it is not routed
+    pub(super) fn get(&self, id: &AudioFiles, path: Option<&str>) -> Option<InsertChannel> {
+        if let Some(route) = self.files.get(id) {
+            return Some(route.insert);
+        }
+        let path = path?.replace('\\', "/");
+        self.folders
+            .iter()
+            .find(|(folder, _)| folder.is_empty() || path.starts_with(&format!("{}/", folder)))
+            .map(|(_, route)| route.insert)
+    }
+}
+
+fn insert_channel<Channel: ACBounds>(commands: &mut EntityCommands) {
+    commands.insert(Channel::default());
+}
+
+/// Files routed to a channel that was never registered would never play, warn about those folders once
+pub(super) fn warn_unregistered_routes(
+    routing: Res<AudioRouting>,
+    registry: Res<AudioChannelRegistry>,
+) {
+    for (folder, route) in routing.folders.iter() {
+        if !registry
+            .iter()
+            .any(|info| info.type_name() == route.channel)
+        {
+            warn!(
+                "The {} folder is routed to {}, which is not registered, its files will not play",
+                folder, route.channel
+            );
+        }
+    }
+}
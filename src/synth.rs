@@ -0,0 +1,379 @@
+use std::{f32::consts::TAU, time::Duration};
+
+#[cfg(feature = "inspect")]
+use bevy::reflect::Reflect;
+use bevy::{
+    asset::Asset,
+    audio::{Decodable, Source},
+    reflect::TypePath,
+};
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// The shape of the wave a [Synth] generates
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+pub enum Waveform {
+    #[default]
+    Sine,
+    Square,
+    Triangle,
+    Sawtooth,
+    /// White noise that picks a new value every period, so the frequency still changes its pitch
+    Noise,
+}
+
+/// A sound that is generated instead of read from a file, in the spirit of sfxr
+///
+/// Register it with [DynamicAudioRegistry::register_synth](crate::dynamic::DynamicAudioRegistry::register_synth)
+/// to get an [AudioFiles](crate::audio_files::AudioFiles) id that plays like any other file
+///
+/// # Example
+/// ```ignore
+/// use bevy::prelude::*;
+/// use bevy_audio_controller::prelude::*;
+///
+/// #[derive(Resource)]
+/// struct Sounds {
+///     beep: AudioFiles,
+///     coin: AudioFiles,
+/// }
+///
+/// fn setup(mut commands: Commands, mut registry: ResMut<DynamicAudioRegistry>, mut synths: ResMut<Assets<SynthSource>>) {
+///     let beep = registry.register_synth(&mut synths, "beep", Synth::square(880.0, 0.1).with_release(0.05));
+///     let coin = registry.register_synth(&mut synths, "coin", Synth::preset(SynthPreset::Coin));
+///     commands.insert_resource(Sounds { beep, coin });
+/// }
+///
+/// fn play_beep(mut ew: EventWriter<PlayEvent<SfxChannel>>, sounds: Res<Sounds>) {
+///     ew.send(PlayEvent::new(sounds.beep));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+pub struct Synth {
+    pub waveform: Waveform,
+    /// The starting frequency in Hz
+    pub frequency: f32,
+    /// How many Hz the frequency changes per second, negative values slide down
+    pub frequency_slide: f32,
+    /// Seconds to fade in
+    pub attack: f32,
+    /// Seconds at full volume
+    pub sustain: f32,
+    /// Seconds to fade out
+    pub release: f32,
+    /// How far the frequency wobbles, as a fraction of the frequency
+    pub vibrato_depth: f32,
+    /// How many times per second the frequency wobbles
+    pub vibrato_speed: f32,
+    /// Scale is 0.0 - 1.0
+    pub volume: f32,
+}
+
+impl Default for Synth {
+    fn default() -> Self {
+        Self {
+            waveform: Waveform::Sine,
+            frequency: 440.0,
+            frequency_slide: 0.0,
+            attack: 0.0,
+            sustain: 0.2,
+            release: 0.0,
+            vibrato_depth: 0.0,
+            vibrato_speed: 0.0,
+            volume: 0.5,
+        }
+    }
+}
+
+impl Synth {
+    /// A tone of a waveform at `frequency` Hz that lasts `seconds`
+    pub fn new(waveform: Waveform, frequency: f32, seconds: f32) -> Self {
+        Self {
+            waveform,
+            frequency,
+            sustain: seconds,
+            ..Default::default()
+        }
+    }
+
+    /// A sine tone at `frequency` Hz that lasts `seconds`
+    pub fn sine(frequency: f32, seconds: f32) -> Self {
+        Self::new(Waveform::Sine, frequency, seconds)
+    }
+
+    /// A square tone at `frequency` Hz that lasts `seconds`
+    pub fn square(frequency: f32, seconds: f32) -> Self {
+        Self::new(Waveform::Square, frequency, seconds)
+    }
+
+    /// A triangle tone at `frequency` Hz that lasts `seconds`
+    pub fn triangle(frequency: f32, seconds: f32) -> Self {
+        Self::new(Waveform::Triangle, frequency, seconds)
+    }
+
+    /// A sawtooth tone at `frequency` Hz that lasts `seconds`
+    pub fn sawtooth(frequency: f32, seconds: f32) -> Self {
+        Self::new(Waveform::Sawtooth, frequency, seconds)
+    }
+
+    /// Noise that lasts `seconds`
+    pub fn noise(seconds: f32) -> Self {
+        Self::new(Waveform::Noise, 8_000.0, seconds)
+    }
+
+    /// One of the classic sfxr sound types
+    pub fn preset(preset: SynthPreset) -> Self {
+        match preset {
+            SynthPreset::Blip => Self::square(1_000.0, 0.05).with_release(0.03),
+            SynthPreset::Coin => Self::square(1_200.0, 0.08)
+                .with_slide(4_000.0)
+                .with_release(0.2),
+            SynthPreset::Laser => Self::sawtooth(1_600.0, 0.08)
+                .with_slide(-8_000.0)
+                .with_release(0.1),
+            SynthPreset::Explosion => Self::noise(0.1)
+                .with_frequency(3_000.0)
+                .with_slide(-4_000.0)
+                .with_release(0.5),
+            SynthPreset::PowerUp => Self::square(300.0, 0.3)
+                .with_slide(1_500.0)
+                .with_vibrato(0.1, 12.0)
+                .with_release(0.2),
+            SynthPreset::Hit => Self::noise(0.02)
+                .with_frequency(2_000.0)
+                .with_slide(-6_000.0)
+                .with_release(0.15),
+            SynthPreset::Jump => Self::square(250.0, 0.12)
+                .with_slide(2_500.0)
+                .with_release(0.1),
+        }
+    }
+
+    /// Sets the starting frequency in Hz
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Sets how many Hz the frequency changes per second
+    pub fn with_slide(mut self, hz_per_second: f32) -> Self {
+        self.frequency_slide = hz_per_second;
+        self
+    }
+
+    /// Sets the seconds to fade in
+    pub fn with_attack(mut self, seconds: f32) -> Self {
+        self.attack = seconds;
+        self
+    }
+
+    /// Sets the seconds to fade out after the sustain
+    pub fn with_release(mut self, seconds: f32) -> Self {
+        self.release = seconds;
+        self
+    }
+
+    /// Makes the frequency wobble by `depth` (a fraction of the frequency) `speed` times per second
+    pub fn with_vibrato(mut self, depth: f32, speed: f32) -> Self {
+        self.vibrato_depth = depth;
+        self.vibrato_speed = speed;
+        self
+    }
+
+    /// Sets the volume, scale is 0.0 - 1.0
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Returns the length of the sound in seconds
+    pub fn duration(&self) -> f32 {
+        self.attack.max(0.0) + self.sustain.max(0.0) + self.release.max(0.0)
+    }
+
+    fn envelope(&self, time: f32) -> f32 {
+        if time < self.attack {
+            time / self.attack
+        } else if time < self.attack + self.sustain {
+            1.0
+        } else if self.release > 0.0 {
+            (1.0 - (time - self.attack - self.sustain) / self.release).max(0.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Sound types of sfxr that [Synth::preset] can generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "inspect", derive(Reflect))]
+pub enum SynthPreset {
+    Blip,
+    Coin,
+    Laser,
+    Explosion,
+    PowerUp,
+    Hit,
+    Jump,
+}
+
+/// The audio source of a registered [Synth]
+#[derive(Asset, TypePath, Clone)]
+pub struct SynthSource {
+    synth: Synth,
+}
+
+impl SynthSource {
+    pub(super) fn new(synth: Synth) -> Self {
+        Self { synth }
+    }
+}
+
+impl Decodable for SynthSource {
+    type DecoderItem = f32;
+    type Decoder = SynthDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        SynthDecoder {
+            synth: self.synth,
+            index: 0,
+            length: (self.synth.duration() * SAMPLE_RATE as f32) as u64,
+            phase: 0.0,
+            noise: 0.0,
+            rng: 0x9e37_79b9,
+        }
+    }
+}
+
+/// Generates the samples of a [Synth] one at a time
+pub struct SynthDecoder {
+    synth: Synth,
+    index: u64,
+    length: u64,
+    /// Position within the current period, 0.0 - 1.0
+    phase: f32,
+    noise: f32,
+    rng: u32,
+}
+
+impl SynthDecoder {
+    fn next_noise(&mut self) -> f32 {
+        // xorshift32, the sound only needs to be noisy, not random
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        self.rng as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+}
+
+impl Iterator for SynthDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.length {
+            return None;
+        }
+        let synth = self.synth;
+        let time = self.index as f32 / SAMPLE_RATE as f32;
+        self.index += 1;
+
+        let vibrato = 1.0 + synth.vibrato_depth * (TAU * synth.vibrato_speed * time).sin();
+        let frequency = ((synth.frequency + synth.frequency_slide * time) * vibrato).max(1.0);
+        let value = match synth.waveform {
+            Waveform::Sine => (TAU * self.phase).sin(),
+            Waveform::Square => {
+                if self.phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Triangle => 1.0 - 4.0 * (self.phase - 0.5).abs(),
+            Waveform::Sawtooth => 2.0 * self.phase - 1.0,
+            Waveform::Noise => self.noise,
+        };
+
+        self.phase += frequency / SAMPLE_RATE as f32;
+        if self.phase >= 1.0 {
+            self.phase %= 1.0;
+            if synth.waveform == Waveform::Noise {
+                self.noise = self.next_noise();
+            }
+        }
+
+        Some(value * synth.envelope(time) * synth.volume.clamp(0.0, 1.0))
+    }
+}
+
+impl Source for SynthDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some((self.length - self.index) as usize)
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(self.synth.duration()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(synth: Synth) -> Vec<f32> {
+        SynthSource::new(synth).decoder().collect()
+    }
+
+    #[test]
+    fn length_matches_the_duration() {
+        let synth = Synth::sine(440.0, 0.2).with_attack(0.1).with_release(0.3);
+        assert_eq!(synth.duration(), 0.6);
+        assert_eq!(samples(synth).len(), (0.6 * SAMPLE_RATE as f32) as usize);
+        assert_eq!(
+            SynthSource::new(synth).decoder().total_duration(),
+            Some(Duration::from_secs_f32(0.6))
+        );
+        // Negative times count as zero
+        assert_eq!(Synth::sine(440.0, 0.1).with_release(-1.0).duration(), 0.1);
+        assert!(samples(Synth::sine(440.0, 0.0)).is_empty());
+    }
+
+    #[test]
+    fn envelope_fades_in_and_out() {
+        let synth = Synth::square(100.0, 0.5)
+            .with_attack(0.5)
+            .with_release(0.5)
+            .with_volume(1.0);
+        assert_eq!(synth.envelope(0.0), 0.0);
+        assert_eq!(synth.envelope(0.25), 0.5);
+        assert_eq!(synth.envelope(0.75), 1.0);
+        assert_eq!(synth.envelope(1.25), 0.5);
+        assert_eq!(synth.envelope(2.0), 0.0);
+        // A square wave is always at full amplitude, so the samples follow the envelope
+        let samples = samples(synth);
+        let at = |seconds: f32| samples[(seconds * SAMPLE_RATE as f32) as usize].abs();
+        assert_eq!(at(0.0), 0.0);
+        assert!((at(0.25) - 0.5).abs() < 1e-3);
+        assert_eq!(at(0.75), 1.0);
+        assert!((at(1.25) - 0.5).abs() < 1e-3);
+        assert!(at(1.499) < 1e-2);
+        assert!(samples.iter().all(|sample| sample.abs() <= 1.0));
+    }
+
+    #[test]
+    fn volume_scales_the_samples() {
+        let loudest = samples(Synth::square(100.0, 0.1).with_volume(0.25))
+            .into_iter()
+            .fold(0.0f32, |max, sample| max.max(sample.abs()));
+        assert_eq!(loudest, 0.25);
+    }
+}
@@ -0,0 +1,305 @@
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    app::{App, Plugin, Startup, Update},
+    asset::{AssetServer, AssetServerMode, Assets, Handle, LoadState},
+    audio::AudioSource,
+    ecs::{
+        schedule::{common_conditions::resource_exists, IntoSystemConfigs},
+        system::{Commands, Res, ResMut, Resource},
+    },
+    log::{info, warn},
+    tasks::{block_on, futures_lite::StreamExt, poll_once, AsyncComputeTaskPool, IoTaskPool, Task},
+};
+
+use crate::{
+    audio_files::{AudioFiles, ALL_FILES},
+    dynamic::count_duration,
+};
+
+/// Checks the files that were found at build time against the files the [AssetServer] can actually read
+///
+/// The generated [AudioFiles] can drift from what ships, e.g. files renamed after the build or a stale `OUT_DIR`.
+/// The results end up in the [AudioValidationReport] resource and every problem is logged as a warning.
+///
+/// # Example
+/// ```ignore
+/// use bevy::prelude::*;
+/// use bevy_audio_controller::prelude::*;
+///
+/// fn main() {
+///     let mut app = App::new();
+///     app.add_plugins((DefaultPlugins, AudioControllerPlugin::default()));
+///     #[cfg(debug_assertions)]
+///     app.add_plugins(AudioValidationPlugin::default());
+///     app.run();
+/// }
+/// ```
+///
+/// It also works headless, e.g. in a test:
+/// ```ignore
+/// #[test]
+/// fn audio_assets_match() {
+///     let mut app = App::new();
+///     app.add_plugins((
+///         MinimalPlugins,
+///         AssetPlugin::default(),
+///         AudioPlugin::default(),
+///         AudioControllerPlugin::default(),
+///         AudioValidationPlugin::default(),
+///     ));
+///     while !app.world().resource::<AudioValidationReport>().is_finished() {
+///         app.update();
+///     }
+///     let report = app.world().resource::<AudioValidationReport>();
+///     assert!(report.is_ok(), "{:?}", report);
+/// }
+/// ```
+pub struct AudioValidationPlugin {
+    duration_tolerance: f32,
+}
+
+impl Default for AudioValidationPlugin {
+    fn default() -> Self {
+        Self {
+            duration_tolerance: 0.1,
+        }
+    }
+}
+
+impl AudioValidationPlugin {
+    /// Sets how many seconds the duration of a file may differ from the one found at build time, defaults to 0.1
+    pub fn with_duration_tolerance(mut self, seconds: f32) -> Self {
+        self.duration_tolerance = seconds;
+        self
+    }
+}
+
+impl Plugin for AudioValidationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AudioValidationReport>()
+            .insert_resource(ValidationTolerance(self.duration_tolerance))
+            .add_systems(Startup, start_validation)
+            .add_systems(
+                Update,
+                poll_validation.run_if(resource_exists::<AudioValidationState>),
+            );
+    }
+}
+
+/// A file whose duration differs from the one found at build time
+#[derive(Debug, Clone, PartialEq)]
+pub struct DurationMismatch {
+    pub id: AudioFiles,
+    /// The duration in seconds found at build time
+    pub expected: f32,
+    /// The duration in seconds of the file that was loaded
+    pub actual: f32,
+}
+
+/// The result of the [AudioValidationPlugin]
+#[derive(Resource, Default, Debug, Clone)]
+pub struct AudioValidationReport {
+    /// Files that were found at build time but can not be read
+    pub missing: Vec<AudioFiles>,
+    /// Audio files that can be read but were not found at build time, as asset paths
+    pub extra: Vec<String>,
+    pub duration_mismatches: Vec<DurationMismatch>,
+    /// Files that can be listed but fail to load, with the reason
+    pub failed: Vec<(AudioFiles, String)>,
+    /// Set if the asset folder could not be listed, e.g. when the asset reader does not support it
+    pub listing_error: Option<String>,
+    finished: bool,
+}
+
+impl AudioValidationReport {
+    /// Returns true once every file has been checked
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns true if the validation finished without finding any problems
+    pub fn is_ok(&self) -> bool {
+        self.finished
+            && self.missing.is_empty()
+            && self.extra.is_empty()
+            && self.duration_mismatches.is_empty()
+            && self.failed.is_empty()
+            && self.listing_error.is_none()
+    }
+}
+
+#[derive(Resource)]
+struct ValidationTolerance(f32);
+
+#[derive(Resource)]
+struct AudioValidationState {
+    listing: Option<Task<Result<Vec<String>, String>>>,
+    pending: Vec<(AudioFiles, Handle<AudioSource>)>,
+    /// Files whose length is counted on the [AsyncComputeTaskPool]
+    measuring: Vec<(AudioFiles, Task<f32>)>,
+}
+
+fn start_validation(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let asset_server = asset_server.clone();
+    let listing = IoTaskPool::get().spawn(async move { list_audio_files(&asset_server).await });
+    commands.insert_resource(AudioValidationState {
+        listing: Some(listing),
+        pending: Vec::new(),
+        measuring: Vec::new(),
+    });
+}
+
+fn poll_validation(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    audio_sources: Res<Assets<AudioSource>>,
+    tolerance: Res<ValidationTolerance>,
+    mut state: ResMut<AudioValidationState>,
+    mut report: ResMut<AudioValidationReport>,
+) {
+    if let Some(task) = state.listing.as_mut() {
+        let Some(result) = block_on(poll_once(task)) else {
+            return;
+        };
+        state.listing = None;
+        match result {
+            Ok(paths) => {
+                for id in ALL_FILES {
+                    if paths.iter().any(|path| path == id.path()) {
+                        state
+                            .pending
+                            .push((id, asset_server.load(id.path().to_string())));
+                    } else {
+                        warn!("Audio file {} was found at build time but is missing", id);
+                        report.missing.push(id);
+                    }
+                }
+                for path in paths {
+                    if !ALL_FILES.iter().any(|id| id.path() == path) {
+                        warn!(
+                            "Audio file {} was not found at build time, try rebuilding",
+                            path
+                        );
+                        report.extra.push(path);
+                    }
+                }
+            }
+            Err(error) => {
+                warn!(
+                    "Unable to list the audio files in the asset folder: {}",
+                    error
+                );
+                report.listing_error = Some(error);
+            }
+        }
+    }
+
+    let state = &mut *state;
+    let mut finished = Vec::new();
+    for (index, (id, handle)) in state.pending.iter().enumerate() {
+        match asset_server.get_load_state(handle) {
+            Some(LoadState::Loaded) => {
+                if let Some(source) = audio_sources.get(handle) {
+                    let source = source.clone();
+                    let task =
+                        AsyncComputeTaskPool::get().spawn(async move { count_duration(&source) });
+                    state.measuring.push((*id, task));
+                }
+                finished.push(index);
+            }
+            Some(LoadState::Failed(error)) => {
+                warn!("Audio file {} failed to load: {}", id, error);
+                report.failed.push((*id, error.to_string()));
+                finished.push(index);
+            }
+            None => {
+                warn!("Audio file {} failed to load", id);
+                report
+                    .failed
+                    .push((*id, "the asset server dropped it".to_string()));
+                finished.push(index);
+            }
+            _ => {}
+        }
+    }
+    for index in finished.into_iter().rev() {
+        state.pending.swap_remove(index);
+    }
+
+    state.measuring.retain_mut(|(id, task)| {
+        let Some(actual) = block_on(poll_once(task)) else {
+            return true;
+        };
+        let expected = id.duration();
+        if (expected - actual).abs() > tolerance.0 {
+            warn!(
+                "Audio file {} is {}s long, but was {}s long at build time",
+                id, actual, expected
+            );
+            report.duration_mismatches.push(DurationMismatch {
+                id: *id,
+                expected,
+                actual,
+            });
+        }
+        false
+    });
+
+    if state.listing.is_none() && state.pending.is_empty() && state.measuring.is_empty() {
+        report.finished = true;
+        if report.is_ok() {
+            info!("All {} audio files match the asset folder", ALL_FILES.len());
+        }
+        commands.remove_resource::<AudioValidationState>();
+    }
+}
+
+/// Walks the default asset source and returns the paths of every supported audio file
+async fn list_audio_files(asset_server: &AssetServer) -> Result<Vec<String>, String> {
+    let source = asset_server
+        .get_source(bevy::asset::io::AssetSourceId::Default)
+        .map_err(|error| error.to_string())?;
+    let reader = match asset_server.mode() {
+        AssetServerMode::Unprocessed => source.reader(),
+        AssetServerMode::Processed => source
+            .processed_reader()
+            .map_err(|error| error.to_string())?,
+    };
+
+    let mut paths = Vec::new();
+    let mut folders = vec![PathBuf::new()];
+    while let Some(folder) = folders.pop() {
+        let mut entries = reader
+            .read_directory(&folder)
+            .await
+            .map_err(|error| error.to_string())?;
+        while let Some(path) = entries.next().await {
+            if reader.is_directory(&path).await.unwrap_or(false) {
+                folders.push(path);
+            } else if is_supported_audio_file(&path) {
+                paths.push(path.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Mirrors the formats the build script looks for
+const FORMATS: &[&str] = &[
+    #[cfg(feature = "flac")]
+    "flac",
+    #[cfg(feature = "mp3")]
+    "mp3",
+    #[cfg(feature = "ogg")]
+    "ogg",
+    #[cfg(feature = "wav")]
+    "wav",
+];
+
+fn is_supported_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| FORMATS.contains(&ext))
+}